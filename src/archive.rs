@@ -0,0 +1,202 @@
+//! Loads every chain, asset list and IBC path in the registry from a single archive, either downloaded from GitHub
+//! or read from disk, instead of requesting each file individually.
+use crate::{
+    assets::AssetList,
+    chain::ChainInfo,
+    error::{RegistryError, Result},
+    paths::IBCPath,
+    source::is_chain_dir,
+};
+use flate2::read::GzDecoder;
+use serde::de::DeserializeOwned;
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufReader, Read},
+    path::{Component, Path},
+};
+
+/// Every [`ChainInfo`], [`AssetList`] and [`IBCPath`] in the registry at a single point in time
+#[derive(Clone, Debug, Default)]
+pub struct RegistrySnapshot {
+    /// `chain.json` contents keyed by chain name
+    pub chains: BTreeMap<String, ChainInfo>,
+    /// `assetlist.json` contents keyed by chain name
+    pub assets: BTreeMap<String, AssetList>,
+    /// `_IBC` path contents keyed by path name in the form <chain_a>-<chain_b>
+    pub paths: BTreeMap<String, IBCPath>,
+    /// `testnets/<chain>/chain.json` contents keyed by chain name
+    pub testnet_chains: BTreeMap<String, ChainInfo>,
+    /// `testnets/<chain>/assetlist.json` contents keyed by chain name
+    pub testnet_assets: BTreeMap<String, AssetList>,
+    /// `testnets/_IBC` path contents keyed by path name in the form <chain_a>-<chain_b>
+    pub testnet_paths: BTreeMap<String, IBCPath>,
+}
+
+impl RegistrySnapshot {
+    /// Reads a snapshot from a gzipped tarball of the registry repository in a single pass. Entries are expected to be
+    /// nested under one top-level directory, as they are in GitHub's tarballs or an archive created with
+    /// `tar -czf registry.tar.gz chain-registry/`.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - A reader over the `.tar.gz` data
+    pub fn from_tar_gz<R: Read>(reader: R) -> Result<RegistrySnapshot> {
+        let mut archive = tar::Archive::new(GzDecoder::new(reader));
+        let mut snapshot = RegistrySnapshot::default();
+
+        let io_err = |e| RegistryError::io("registry archive", e);
+
+        for entry in archive.entries().map_err(io_err)? {
+            let entry = entry.map_err(io_err)?;
+
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+
+            let path = entry.path().map_err(io_err)?.into_owned();
+            let components: Vec<&str> = path
+                .components()
+                .filter_map(|c| match c {
+                    Component::Normal(c) => c.to_str(),
+                    _ => None,
+                })
+                .collect();
+
+            // skip the top-level directory
+            match components[..] {
+                [_, "_IBC", file] if !file.starts_with('_') && file.ends_with(".json") => {
+                    let name = &file[..file.len() - ".json".len()];
+                    snapshot
+                        .paths
+                        .insert(name.to_string(), parse_entry(entry, &path)?);
+                }
+                [_, chain, "chain.json"] if is_chain_dir(chain) => {
+                    snapshot
+                        .chains
+                        .insert(chain.to_string(), parse_entry(entry, &path)?);
+                }
+                [_, chain, "assetlist.json"] if is_chain_dir(chain) => {
+                    snapshot
+                        .assets
+                        .insert(chain.to_string(), parse_entry(entry, &path)?);
+                }
+                [_, "testnets", "_IBC", file]
+                    if !file.starts_with('_') && file.ends_with(".json") =>
+                {
+                    let name = &file[..file.len() - ".json".len()];
+                    snapshot
+                        .testnet_paths
+                        .insert(name.to_string(), parse_entry(entry, &path)?);
+                }
+                [_, "testnets", chain, "chain.json"] if !chain.starts_with('_') => {
+                    snapshot
+                        .testnet_chains
+                        .insert(chain.to_string(), parse_entry(entry, &path)?);
+                }
+                [_, "testnets", chain, "assetlist.json"] if !chain.starts_with('_') => {
+                    snapshot
+                        .testnet_assets
+                        .insert(chain.to_string(), parse_entry(entry, &path)?);
+                }
+                _ => continue,
+            }
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Reads a snapshot from a gzipped tarball of the registry repository on disk. See [`RegistrySnapshot::from_tar_gz`].
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the `.tar.gz` file
+    pub fn from_tar_gz_file(path: impl AsRef<Path>) -> Result<RegistrySnapshot> {
+        let path = path.as_ref();
+        let file =
+            File::open(path).map_err(|e| RegistryError::io(path.display().to_string(), e))?;
+
+        Self::from_tar_gz(BufReader::new(file))
+    }
+}
+
+fn parse_entry<T, R>(entry: R, path: &Path) -> Result<T>
+where
+    T: DeserializeOwned,
+    R: Read,
+{
+    serde_json::from_reader(entry).map_err(|e| RegistryError::parse(path.display().to_string(), e))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use assay::assay;
+    use flate2::{write::GzEncoder, Compression};
+
+    pub(crate) fn fixture_tar_gz() -> Vec<u8> {
+        let encoder = GzEncoder::new(Vec::new(), Compression::fast());
+        let mut builder = tar::Builder::new(encoder);
+        builder
+            .append_dir_all(
+                "cosmos-chain-registry-350840e",
+                concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/registry"),
+            )
+            .unwrap();
+
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    #[assay]
+    fn reads_snapshot_from_tar_gz() {
+        let snapshot = RegistrySnapshot::from_tar_gz(fixture_tar_gz().as_slice()).unwrap();
+
+        assert_eq!(
+            snapshot.chains.keys().collect::<Vec<_>>(),
+            vec!["cosmoshub", "juno", "osmosis"]
+        );
+        assert_eq!(
+            snapshot.assets.keys().collect::<Vec<_>>(),
+            vec!["cosmoshub", "osmosis"]
+        );
+        assert_eq!(
+            snapshot.paths.keys().collect::<Vec<_>>(),
+            vec!["cosmoshub-osmosis", "juno-osmosis"]
+        );
+        assert_eq!(snapshot.chains["cosmoshub"].chain_id, "cosmoshub-4");
+        assert_eq!(
+            snapshot.testnet_chains.keys().collect::<Vec<_>>(),
+            vec!["junotestnet", "osmosistestnet"]
+        );
+        assert_eq!(
+            snapshot.testnet_paths.keys().collect::<Vec<_>>(),
+            vec!["junotestnet-osmosistestnet"]
+        );
+        assert_eq!(
+            snapshot.paths["juno-osmosis"].channels[0]
+                .chain_2
+                .channel_id,
+            "channel-42"
+        );
+    }
+
+    #[assay]
+    fn invalid_json_errors() {
+        let encoder = GzEncoder::new(Vec::new(), Compression::fast());
+        let mut builder = tar::Builder::new(encoder);
+        let data = b"{ not json";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "registry/osmosis/chain.json", &data[..])
+            .unwrap();
+        let archive = builder.into_inner().unwrap().finish().unwrap();
+
+        let err = RegistrySnapshot::from_tar_gz(archive.as_slice()).unwrap_err();
+
+        assert!(
+            matches!(err, RegistryError::Parse { path, .. } if path == "registry/osmosis/chain.json")
+        );
+    }
+}
@@ -1,15 +1,36 @@
 /// Contains models for serializing and deserializing `assets.json` for a given chain
+use crate::serde_util::{models, Present};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+models!(
+    AssetList,
+    Asset,
+    Trace,
+    TraceCounterparty,
+    TraceChain,
+    DenomUnit,
+    LogoURIs
+);
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
-#[serde(default,)]
+#[serde(default, remote = "Self")]
 pub struct AssetList {
+    #[serde(rename = "$schema")]
+    pub schema: String,
     pub chain_name: String,
     pub assets: Vec<Asset>,
+    /// Keys not covered by the fields above. Written back on serialization.
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
-#[serde(default,)]
+#[serde(default, remote = "Self")]
 pub struct Asset {
     pub description: String,
     pub denom_units: Vec<DenomUnit>,
@@ -20,18 +41,79 @@ pub struct Asset {
     #[serde(rename = "logo_URIs")]
     pub logo_uris: LogoURIs,
     pub coingecko_id: String,
+    /// The hops the asset took to reach this chain, ending with the chain it was received from
+    pub traces: Vec<Trace>,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
+}
+
+/// Describes how an asset was derived from an asset on another chain, e.g. by an IBC transfer or a bridge
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default, remote = "Self")]
+pub struct Trace {
+    /// The kind of hop, e.g. `ibc`, `ibc-cw20`, `bridge`, `wrapped` or `liquid-stake`
+    #[serde(rename = "type")]
+    pub type_field: String,
+    pub counterparty: TraceCounterparty,
+    pub chain: Option<TraceChain>,
+    pub provider: Option<String>,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
+}
+
+/// The asset a [`Trace`] was derived from. `chain_name` may name a chain in `_non-cosmos`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default, remote = "Self")]
+pub struct TraceCounterparty {
+    pub chain_name: String,
+    pub base_denom: String,
+    pub channel_id: Option<String>,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
+}
+
+/// The side of a [`Trace`] on the chain holding the asset
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default, remote = "Self")]
+pub struct TraceChain {
+    pub channel_id: Option<String>,
+    pub path: Option<String>,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
-#[serde(default,)]
+#[serde(default, remote = "Self")]
 pub struct DenomUnit {
     pub denom: String,
     pub exponent: u16,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
-#[serde(default,)]
+#[serde(default, remote = "Self")]
 pub struct LogoURIs {
     pub png: String,
     pub svg: String,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
 }
@@ -0,0 +1,245 @@
+#![cfg(feature = "blocking")]
+#![cfg_attr(docsrs, doc(cfg(feature = "blocking")))]
+//! Synchronous equivalents of the [`get`](crate::get) functions and [`RegistryCache`](crate::cache::RegistryCache) for
+//! programs that don't otherwise use an async runtime. Each call drives the request on a single-threaded tokio runtime
+//! of its own, so these functions panic if called from within an async context.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use chain_registry::blocking;
+//!
+//! fn main() {
+//!     let chain = blocking::get_chain("osmosis").unwrap().unwrap();
+//!     println!("{}", chain.chain_id);
+//! }
+//! ```
+use crate::{assets::AssetList, chain::ChainInfo, error::Result, get, paths::IBCPath};
+use std::future::Future;
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to build tokio runtime")
+        .block_on(future)
+}
+
+/// Gets a list of chain names from the registry
+pub fn list_chains() -> Result<Vec<String>> {
+    block_on(get::list_chains())
+}
+
+/// Gets a list of path names from the registry in the form <chain_a>-<chain_b>
+pub fn list_paths() -> Result<Vec<String>> {
+    block_on(get::list_paths())
+}
+
+/// Retrieves the deserialized `assets.json` for a given chain. See [`get::get_assets`].
+///
+/// # Arguments
+///
+/// * `name` - The chain name. Must match the name of the chain's folder in the root directory of the
+///   [chain registry](https://github.com/cosmos/chain-registry).
+pub fn get_assets(name: &str) -> Result<Option<AssetList>> {
+    block_on(get::get_assets(name))
+}
+
+/// Retrieves the deserialized `chain.json` for a given chain. See [`get::get_chain`].
+///
+/// # Arguments
+///
+/// * `name` - The chain name. Must match the name of the chain's folder in the root directory of the
+///   [chain registry](https://github.com/cosmos/chain-registry).
+pub fn get_chain(name: &str) -> Result<Option<ChainInfo>> {
+    block_on(get::get_chain(name))
+}
+
+/// Retrieves the deserialized IBC path json for a given pair of chains. See [`get::get_path`].
+///
+/// # Arguments
+///
+/// * `chain_a` - A chain name. Must match the name of a chain's folder in the root directory of the
+///   [chain registry](https://github.com/cosmos/chain-registry).
+/// * `chain_b` - A chain name. Must match the name of a chain's folder in the root directory of the
+///   [chain registry](https://github.com/cosmos/chain-registry).
+pub fn get_path(chain_a: &str, chain_b: &str) -> Result<Option<IBCPath>> {
+    block_on(get::get_path(chain_a, chain_b))
+}
+
+/// Gets a list of testnet chain names from the `testnets` directory of the registry
+pub fn list_testnets() -> Result<Vec<String>> {
+    block_on(get::list_testnets())
+}
+
+/// Retrieves the deserialized `assetlist.json` for a chain in the `testnets` directory. See
+/// [`get::get_testnet_assets`].
+pub fn get_testnet_assets(name: &str) -> Result<Option<AssetList>> {
+    block_on(get::get_testnet_assets(name))
+}
+
+/// Retrieves the deserialized `chain.json` for a chain in the `testnets` directory. See [`get::get_testnet_chain`].
+pub fn get_testnet_chain(name: &str) -> Result<Option<ChainInfo>> {
+    block_on(get::get_testnet_chain(name))
+}
+
+/// Retrieves the deserialized IBC path json in `testnets/_IBC` for a given pair of testnets. See
+/// [`get::get_testnet_path`].
+pub fn get_testnet_path(chain_a: &str, chain_b: &str) -> Result<Option<IBCPath>> {
+    block_on(get::get_testnet_path(chain_a, chain_b))
+}
+
+/// Gets a list of the chains outside the Cosmos ecosystem with an asset list in the `_non-cosmos` directory
+pub fn list_non_cosmos_chains() -> Result<Vec<String>> {
+    block_on(get::list_non_cosmos_chains())
+}
+
+/// Retrieves the deserialized `assetlist.json` for a chain in the `_non-cosmos` directory. See
+/// [`get::get_non_cosmos_assets`].
+pub fn get_non_cosmos_assets(name: &str) -> Result<Option<AssetList>> {
+    block_on(get::get_non_cosmos_assets(name))
+}
+
+#[cfg(feature = "cache")]
+pub use self::cache::RegistryCache;
+
+#[cfg(feature = "cache")]
+mod cache {
+    use super::block_on;
+    use crate::{
+        archive::RegistrySnapshot,
+        cache,
+        error::Result,
+        local::LocalRegistry,
+        paths::{IBCPath, Tag},
+        source::{Network, RegistrySource},
+    };
+
+    /// A synchronous wrapper around [`cache::RegistryCache`]
+    #[derive(Default)]
+    pub struct RegistryCache {
+        inner: cache::RegistryCache,
+    }
+
+    impl RegistryCache {
+        /// Creates a new cache by retrieving and deserializing each [`IBCPath`] from the Cosmos Chain Registry. See
+        /// [`cache::RegistryCache::try_new`].
+        pub fn try_new() -> Result<RegistryCache> {
+            block_on(cache::RegistryCache::try_new()).map(RegistryCache::from)
+        }
+
+        /// Creates a new cache by reading and deserializing each [`IBCPath`] from a local checkout of the registry
+        ///
+        /// # Arguments
+        ///
+        /// * `registry` - A [`LocalRegistry`] pointing at the root directory of the registry checkout
+        pub fn try_from_local(registry: &LocalRegistry) -> Result<RegistryCache> {
+            block_on(cache::RegistryCache::try_from_local(registry)).map(RegistryCache::from)
+        }
+
+        /// Creates a new cache by retrieving and deserializing each [`IBCPath`] from any [`RegistrySource`], up to
+        /// `concurrency` at a time
+        ///
+        /// # Arguments
+        ///
+        /// * `source` - The [`RegistrySource`] to read paths from
+        /// * `concurrency` - The maximum number of requests in flight
+        pub fn try_from_source_with_concurrency<S>(
+            source: &S,
+            concurrency: usize,
+        ) -> Result<RegistryCache>
+        where
+            S: RegistrySource + ?Sized,
+        {
+            block_on(cache::RegistryCache::try_from_source_with_concurrency(
+                source,
+                concurrency,
+            ))
+            .map(RegistryCache::from)
+        }
+
+        /// Creates a new cache holding both mainnet and testnet paths from any [`RegistrySource`]. See
+        /// [`cache::RegistryCache::try_from_source_with_testnets`].
+        ///
+        /// # Arguments
+        ///
+        /// * `source` - The [`RegistrySource`] to read paths from
+        /// * `concurrency` - The maximum number of requests in flight
+        pub fn try_from_source_with_testnets<S>(
+            source: &S,
+            concurrency: usize,
+        ) -> Result<RegistryCache>
+        where
+            S: RegistrySource + ?Sized,
+        {
+            block_on(cache::RegistryCache::try_from_source_with_testnets(
+                source,
+                concurrency,
+            ))
+            .map(RegistryCache::from)
+        }
+
+        /// Creates a new cache from the paths in a [`RegistrySnapshot`] without sending any further requests
+        pub fn from_snapshot(snapshot: RegistrySnapshot) -> RegistryCache {
+            cache::RegistryCache::from_snapshot(snapshot).into()
+        }
+
+        /// Returns a cached [`IBCPath`] representing a channel between `chain_a` and `chain_b` if it exists. See
+        /// [`cache::RegistryCache::get_path`].
+        pub fn get_path(&self, chain_a: &str, chain_b: &str) -> Result<Option<IBCPath>> {
+            Ok(self.inner.find_path(Network::Mainnet, chain_a, chain_b))
+        }
+
+        /// Returns cached [`IBCPath`] that match a provided [`Tag`]
+        pub fn get_paths_filtered(&self, tag: Tag) -> Result<Vec<IBCPath>> {
+            Ok(self.inner.filter_paths(Network::Mainnet, &tag))
+        }
+
+        /// Returns a cached testnet [`IBCPath`] between `chain_a` and `chain_b` if it exists. See
+        /// [`cache::RegistryCache::get_testnet_path`].
+        pub fn get_testnet_path(&self, chain_a: &str, chain_b: &str) -> Result<Option<IBCPath>> {
+            Ok(self.inner.find_path(Network::Testnet, chain_a, chain_b))
+        }
+
+        /// Returns cached testnet [`IBCPath`] that match a provided [`Tag`]
+        pub fn get_testnet_paths_filtered(&self, tag: Tag) -> Result<Vec<IBCPath>> {
+            Ok(self.inner.filter_paths(Network::Testnet, &tag))
+        }
+
+        /// Returns the underlying async cache
+        pub fn into_inner(self) -> cache::RegistryCache {
+            self.inner
+        }
+    }
+
+    impl From<cache::RegistryCache> for RegistryCache {
+        fn from(inner: cache::RegistryCache) -> Self {
+            RegistryCache { inner }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use assay::assay;
+
+        #[assay]
+        fn builds_cache_without_a_runtime() {
+            let registry = LocalRegistry::new(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/fixtures/registry"
+            ));
+            let cache = RegistryCache::try_from_local(&registry).unwrap();
+
+            let path = cache.get_path("osmosis", "cosmoshub").unwrap().unwrap();
+            assert_eq!(path.chain_1.chain_name, "cosmoshub");
+            assert_eq!(
+                cache
+                    .get_paths_filtered(Tag::Dex("osmosis".to_string()))
+                    .unwrap()
+                    .len(),
+                2
+            );
+        }
+    }
+}
@@ -1,23 +1,27 @@
 #![cfg(feature = "cache")]
 #![cfg_attr(docsrs, doc(cfg(feature = "cache")))]
 /// Provides caching of registry data for easy querying and filtering. It's recommended to populate the cache during the startup
-/// for a long-running process as construction involves sending an individual GET request for every path in the registry which
-/// takes a while.
+/// for a long-running process as construction involves sending an individual GET request for every path in the registry. Requests
+/// are sent concurrently, up to [`DEFAULT_CONCURRENCY`] at a time unless configured otherwise.
 use crate::{
-    get::*,
+    archive::RegistrySnapshot,
+    client::RegistryClient,
+    error::Result,
+    local::LocalRegistry,
     paths::{IBCPath, Tag},
+    source::{Network, RegistrySource, DEFAULT_CONCURRENCY},
 };
-use eyre::Result;
 use serde::{Deserialize, Serialize};
 use std::{cmp::Ordering, collections::HashMap};
 
 // TO-DO:
-// - Option to load from local repo clone
 // - Currently don't see a need to cache chain/asset info but might need it in the future
 /// Used to cache IBC path data from the chain registry for easy filtering.
 #[derive(Default, Deserialize, Serialize)]
 pub struct RegistryCache {
     paths: HashMap<String, IBCPath>,
+    #[serde(default)]
+    testnet_paths: HashMap<String, IBCPath>,
 }
 
 impl RegistryCache {
@@ -29,13 +33,18 @@ impl RegistryCache {
     /// * `chain_a` - A chain name. Must match a directory name in the root of the chain registry repository `<https://github.com/cosmos/chain-registry>`
     /// * `chain_b` - A chain name. Must match a directory name in the root of the chain registry repository `<https://github.com/cosmos/chain-registry>`
     pub async fn get_path(&self, chain_a: &str, chain_b: &str) -> Result<Option<IBCPath>> {
-        let path_name = match chain_a.cmp(chain_b) {
-            Ordering::Less => chain_a.to_string() + "-" + chain_b,
-            Ordering::Equal => return Ok(None),
-            Ordering::Greater => chain_b.to_string() + "-" + chain_a,
-        };
+        Ok(self.find_path(Network::Mainnet, chain_a, chain_b))
+    }
 
-        Ok(self.paths.get(&path_name).cloned())
+    /// Like [`RegistryCache::get_path`], but for the testnet paths in `testnets/_IBC`. Always returns `Ok(None)` unless
+    /// the cache was created with [`RegistryCache::try_from_source_with_testnets`] or from a snapshot.
+    ///
+    /// # Arguments
+    ///
+    /// * `chain_a` - A chain name. Must match a directory name in the `testnets` directory of the chain registry
+    /// * `chain_b` - A chain name. Must match a directory name in the `testnets` directory of the chain registry
+    pub async fn get_testnet_path(&self, chain_a: &str, chain_b: &str) -> Result<Option<IBCPath>> {
+        Ok(self.find_path(Network::Testnet, chain_a, chain_b))
     }
 
     /// Returns cached [`IBCPath`] that match a provided [`Tag`]
@@ -57,35 +66,159 @@ impl RegistryCache {
     /// let paths = cache.get_paths_filtered(Tag::Dex(dex))?;
     /// ```
     pub async fn get_paths_filtered(&self, tag: Tag) -> Result<Vec<IBCPath>> {
-        Ok(self
-            .paths
-            .iter()
-            .filter(|kv| match &tag {
-                Tag::Dex(d) => kv.1.channels[0].tags.dex.eq(d),
-                Tag::Preferred(p) => kv.1.channels[0].tags.preferred.eq(p),
-                Tag::Properties(p) => kv.1.channels[0].tags.properties.eq(p),
-                Tag::Status(s) => kv.1.channels[0].tags.status.eq(s),
-            })
-            .map(|kv| kv.1.to_owned())
-            .collect())
+        Ok(self.filter_paths(Network::Mainnet, &tag))
+    }
+
+    /// Like [`RegistryCache::get_paths_filtered`], but for the cached testnet paths
+    ///
+    /// # Arguments
+    ///
+    /// * `tag` - A [`Tag`] representing the the desired key/value pair to filter by.
+    pub async fn get_testnet_paths_filtered(&self, tag: Tag) -> Result<Vec<IBCPath>> {
+        Ok(self.filter_paths(Network::Testnet, &tag))
+    }
+
+    /// The cached path between `chain_a` and `chain_b` in `network`, if any. Shared with the blocking wrapper, which
+    /// has no runtime to drive the async getters.
+    pub(crate) fn find_path(
+        &self,
+        network: Network,
+        chain_a: &str,
+        chain_b: &str,
+    ) -> Option<IBCPath> {
+        find_path(self.network_paths(network), chain_a, chain_b)
+    }
+
+    /// The cached paths in `network` matching `tag`
+    pub(crate) fn filter_paths(&self, network: Network, tag: &Tag) -> Vec<IBCPath> {
+        filter_paths(self.network_paths(network), tag)
+    }
+
+    fn network_paths(&self, network: Network) -> &HashMap<String, IBCPath> {
+        match network {
+            Network::Mainnet => &self.paths,
+            Network::Testnet => &self.testnet_paths,
+        }
     }
 
     /// Creates a new cache by retrieving and deserializing each [`IBCPath`] from the Cosmos Chain Registry for easy filtering
     pub async fn try_new() -> Result<RegistryCache> {
-        let path_names = list_paths().await?;
-        let mut paths = HashMap::<String, IBCPath>::default();
-
-        for pn in path_names {
-            let cn: Vec<&str> = pn.split('-').collect();
-
-            // this unwrap is safe becauase we query the path directly from the list of path .json file names
-            // retrieved earlier, therefore the Option returned should never be None.
-            paths.insert(
-                pn.clone(),
-                get_path(cn[0], cn[1]).await?.expect("path returned None"),
-            );
+        Self::try_from_source(&RegistryClient::default()).await
+    }
+
+    /// Creates a new cache by reading and deserializing each [`IBCPath`] from a local checkout of the registry
+    ///
+    /// # Arguments
+    ///
+    /// * `registry` - A [`LocalRegistry`] pointing at the root directory of the registry checkout
+    pub async fn try_from_local(registry: &LocalRegistry) -> Result<RegistryCache> {
+        Self::try_from_source(registry).await
+    }
+
+    /// Creates a new cache by retrieving and deserializing each [`IBCPath`] from any [`RegistrySource`]
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The [`RegistrySource`] to read paths from
+    pub async fn try_from_source<S>(source: &S) -> Result<RegistryCache>
+    where
+        S: RegistrySource + ?Sized,
+    {
+        Self::try_from_source_with_concurrency(source, DEFAULT_CONCURRENCY).await
+    }
+
+    /// Creates a new cache from any [`RegistrySource`], retrieving up to `concurrency` paths at once. Fails with the
+    /// error of the first path, in listing order, that couldn't be retrieved or parsed.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The [`RegistrySource`] to read paths from
+    /// * `concurrency` - The maximum number of requests in flight
+    pub async fn try_from_source_with_concurrency<S>(
+        source: &S,
+        concurrency: usize,
+    ) -> Result<RegistryCache>
+    where
+        S: RegistrySource + ?Sized,
+    {
+        Ok(RegistryCache {
+            paths: load_paths(source, Network::Mainnet, concurrency).await?,
+            testnet_paths: HashMap::default(),
+        })
+    }
+
+    /// Creates a new cache holding both the mainnet paths in `_IBC` and the testnet paths in `testnets/_IBC` of any
+    /// [`RegistrySource`], retrieving up to `concurrency` paths at once
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The [`RegistrySource`] to read paths from
+    /// * `concurrency` - The maximum number of requests in flight
+    pub async fn try_from_source_with_testnets<S>(
+        source: &S,
+        concurrency: usize,
+    ) -> Result<RegistryCache>
+    where
+        S: RegistrySource + ?Sized,
+    {
+        Ok(RegistryCache {
+            paths: load_paths(source, Network::Mainnet, concurrency).await?,
+            testnet_paths: load_paths(source, Network::Testnet, concurrency).await?,
+        })
+    }
+
+    /// Creates a new cache from the paths in a [`RegistrySnapshot`] without sending any further requests
+    ///
+    /// # Arguments
+    ///
+    /// * `snapshot` - A [`RegistrySnapshot`] loaded from a registry archive
+    pub fn from_snapshot(snapshot: RegistrySnapshot) -> RegistryCache {
+        RegistryCache {
+            paths: snapshot.paths.into_iter().collect(),
+            testnet_paths: snapshot.testnet_paths.into_iter().collect(),
         }
+    }
+}
+
+/// Retrieves every path of a network, failing with the error of the first path that couldn't be retrieved or parsed
+async fn load_paths<S>(
+    source: &S,
+    network: Network,
+    concurrency: usize,
+) -> Result<HashMap<String, IBCPath>>
+where
+    S: RegistrySource + ?Sized,
+{
+    let source = source.network(network);
+    let path_names = source.list_paths().await?;
+    let mut paths = HashMap::<String, IBCPath>::default();
 
-        Ok(RegistryCache { paths })
+    for (pn, path) in source.get_paths(&path_names, concurrency).await {
+        paths.insert(pn, path?);
     }
+
+    Ok(paths)
+}
+
+fn find_path(paths: &HashMap<String, IBCPath>, chain_a: &str, chain_b: &str) -> Option<IBCPath> {
+    let path_name = match chain_a.cmp(chain_b) {
+        Ordering::Less => chain_a.to_string() + "-" + chain_b,
+        Ordering::Equal => return None,
+        Ordering::Greater => chain_b.to_string() + "-" + chain_a,
+    };
+
+    paths.get(&path_name).cloned()
+}
+
+fn filter_paths(paths: &HashMap<String, IBCPath>, tag: &Tag) -> Vec<IBCPath> {
+    paths
+        .values()
+        .filter(|path| match tag {
+            Tag::Dex(d) => path.channels[0].tags.dex.eq(d),
+            Tag::Preferred(p) => path.channels[0].tags.preferred.eq(p),
+            Tag::Properties(p) => path.channels[0].tags.properties.eq(p),
+            Tag::Status(s) => path.channels[0].tags.status.eq(s),
+        })
+        .cloned()
+        .collect()
 }
@@ -1,26 +1,68 @@
 #![allow(clippy::derive_partial_eq_without_eq)]
 /// Contains models for serializing and deserializing the `chain.json` in a given chain's directory in the registry repository
+use crate::serde_util::{models, Present};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+models!(
+    ChainInfo,
+    Genesis,
+    Codebase,
+    Version,
+    Dependency,
+    Cosmwasm,
+    Ibc,
+    Peers,
+    Seed,
+    PersistentPeer,
+    Apis,
+    Rpc,
+    Rest,
+    Grpc,
+    Endpoint,
+    Fees,
+    FeeToken,
+    GasCosts,
+    Staking,
+    StakingToken,
+    LockDuration,
+    Explorer,
+    Bech32Config,
+    ImageURIs,
+    Image,
+    ImageSync,
+    ImageTheme
+);
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
-// by denying unknown fields we can be more confident that our structs match the
-// current configured GIT_REF's schema. errors will occur if the chain.json is
-// formatted incorrectly, however.
-#[serde(default,)]
+// every field defaults so that schema drift in the registry doesn't break parsing.
+// use `diagnostics::parse` or `get_chain_checked` to find out what didn't match.
+#[serde(default, remote = "Self")]
 pub struct ChainInfo {
     #[serde(rename = "$schema")]
     pub schema: String,
     pub chain_name: String,
+    /// The chain's ecosystem, e.g. `cosmos`, `eip155` or `bip122`
+    pub chain_type: Option<String>,
+    /// The name the chain was registered under before a hard fork changed its chain ID
+    pub pre_fork_chain_name: Option<String>,
     pub status: String,
     pub network_type: String,
     pub pretty_name: String,
+    pub description: Option<String>,
     pub chain_id: String,
     pub bech32_prefix: String,
+    pub bech32_config: Option<Bech32Config>,
     pub daemon_name: String,
     pub node_home: String,
     pub slip44: u32,
+    /// Other coin types wallets may derive keys with, e.g. for chains that changed their `slip44`
+    pub alternative_slip44s: Vec<u32>,
     pub genesis: Genesis,
     pub codebase: Codebase,
+    /// Codebases of additional binaries the chain needs, e.g. a sidecar process
+    pub extra_codebase: Vec<Codebase>,
     pub peers: Peers,
     pub apis: Apis,
     pub fees: Fees,
@@ -29,131 +71,678 @@ pub struct ChainInfo {
     pub update_link: String,
     pub key_algos: Vec<String>,
     pub explorers: Vec<Explorer>,
+    pub images: Vec<Image>,
+    #[serde(rename = "logo_URIs")]
+    pub logo_uris: Option<ImageURIs>,
+    pub keywords: Vec<String>,
+    /// Keys not covered by the fields above, including those of fields added to the registry schema after this
+    /// crate's release. They're written back on serialization so that editing a file doesn't drop them.
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
-#[serde(default,)]
+#[serde(default, remote = "Self")]
 pub struct Genesis {
+    pub name: Option<String>,
+    /// Where the genesis file can be downloaded, possibly compressed with gzip or packed in a tarball
     pub genesis_url: String,
+    /// The hex-encoded SHA-256 of the genesis file
+    pub genesis_sha256: Option<String>,
+    /// Where the cross-chain validation section of a consumer chain's genesis can be downloaded. It has to be added
+    /// to the genesis file before the chain starts.
+    pub ics_ccv_url: Option<String>,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
-#[serde(default,)]
+#[serde(default, remote = "Self")]
 pub struct Codebase {
     pub git_repo: String,
     pub recommended_version: String,
-    #[serde(skip_serializing_if = "Vec::is_empty", default = "Vec::new")]
     pub compatible_versions: Vec<String>,
     pub binaries: Binaries,
     pub cosmos_sdk_version: String,
     pub tendermint_version: String,
     pub cosmwasm_version: String,
     pub cosmwasm_enabled: bool,
+    pub cosmwasm_path: Option<String>,
+    pub ibc_go_version: Option<String>,
+    /// The IBC application standards the chain supports, e.g. `ics20-1`
+    pub ics_enabled: Vec<String>,
+    pub tag: Option<String>,
+    pub language: Option<Dependency>,
+    pub sdk: Option<Dependency>,
+    pub consensus: Option<Dependency>,
+    pub cosmwasm: Option<Cosmwasm>,
+    pub ibc: Option<Ibc>,
+    /// Every version the chain has run or has scheduled, one per upgrade, in upgrade order
+    pub versions: Vec<Version>,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
+}
+
+impl Codebase {
+    /// Returns the version that processes blocks at `height`: the one with the highest upgrade height at or below it.
+    /// At an upgrade height the previous binary halts and the new version takes over, so that height belongs to the
+    /// new version. The first version is assumed to run from genesis if it has no height. Versions without a height
+    /// that aren't first, such as upgrades that haven't been scheduled yet, are never returned.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use chain_registry::get::get_chain;
+    ///
+    /// let chain = get_chain("osmosis").await?.unwrap();
+    /// if let Some(version) = chain.codebase.version_at_height(10_000_000) {
+    ///     println!("sync with {}", version.recommended_version.as_deref().unwrap_or(&version.name));
+    /// }
+    /// ```
+    pub fn version_at_height(&self, height: u64) -> Option<&Version> {
+        self.upgrade_heights()
+            .filter(|(start, _)| *start <= height)
+            .max_by_key(|(start, _)| *start)
+            .map(|(_, version)| version)
+    }
+
+    /// Returns the first upgrade scheduled above `height`, i.e. the version a node synced to `height` will have to
+    /// switch to next, if it's known
+    pub fn next_upgrade_after(&self, height: u64) -> Option<&Version> {
+        self.upgrade_heights()
+            .filter(|(start, _)| *start > height)
+            .min_by_key(|(start, _)| *start)
+            .map(|(_, version)| version)
+    }
+
+    /// Pairs each version with the height it starts at
+    fn upgrade_heights(&self) -> impl Iterator<Item = (u64, &Version)> {
+        self.versions
+            .iter()
+            .enumerate()
+            .filter_map(|(i, v)| match v.height {
+                Some(height) => Some((height, v)),
+                None if i == 0 => Some((0, v)),
+                None => None,
+            })
+    }
+}
+
+/// A version of a chain's codebase, as recorded for each upgrade in `codebase.versions`
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, remote = "Self")]
+pub struct Version {
+    /// The upgrade name, e.g. `v25`, as used by the chain's upgrade handler
+    pub name: String,
+    pub tag: Option<String>,
+    /// The height the upgrade to this version took or will take place at
+    pub height: Option<u64>,
+    /// The number of the governance proposal that scheduled the upgrade
+    pub proposal: Option<u64>,
+    pub previous_version_name: Option<String>,
+    pub next_version_name: Option<String>,
+    pub recommended_version: Option<String>,
+    pub compatible_versions: Vec<String>,
+    pub binaries: Option<Binaries>,
+    pub cosmos_sdk_version: Option<String>,
+    pub tendermint_version: Option<String>,
+    pub cosmwasm_version: Option<String>,
+    pub cosmwasm_enabled: Option<bool>,
+    pub cosmwasm_path: Option<String>,
+    pub ibc_go_version: Option<String>,
+    pub ics_enabled: Vec<String>,
+    pub language: Option<Dependency>,
+    pub sdk: Option<Dependency>,
+    pub consensus: Option<Dependency>,
+    pub cosmwasm: Option<Cosmwasm>,
+    pub ibc: Option<Ibc>,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
+}
+
+/// A language, framework or library a codebase is built on. `type_field` names it, e.g. `go`, `cosmos` or `cometbft`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, remote = "Self")]
+pub struct Dependency {
+    #[serde(rename = "type")]
+    pub type_field: Option<String>,
+    pub version: Option<String>,
+    pub repo: Option<String>,
+    pub tag: Option<String>,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, remote = "Self")]
+pub struct Cosmwasm {
+    pub version: Option<String>,
+    pub repo: Option<String>,
+    pub tag: Option<String>,
+    pub enabled: Option<bool>,
+    pub path: Option<String>,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
-#[serde(default,)]
-pub struct Binaries {
-    #[serde(rename = "linux/amd64")]
-    pub linux_amd_64: String,
-    #[serde(rename = "linux/arm64")]
-    pub linux_arm_64: String,
-    #[serde(rename = "darwin/amd64")]
-    pub darwin_amd_64: String,
-    #[serde(rename = "darwin/arm64")]
-    pub darwin_arm_64: String,
-    #[serde(rename = "windows/amd64")]
-    pub windows_amd_64: String,
+#[serde(default, remote = "Self")]
+pub struct Ibc {
+    #[serde(rename = "type")]
+    pub type_field: Option<String>,
+    pub version: Option<String>,
+    pub repo: Option<String>,
+    pub tag: Option<String>,
+    pub ics_enabled: Vec<String>,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
 }
 
+/// Download URLs of a codebase's binaries keyed by platform in the form `<os>/<arch>`, e.g. `linux/amd64` or
+/// `darwin/arm64`. URLs may end with a `?checksum=<algorithm>:<digest>` query, which [`Binaries::get`] parses out.
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
-#[serde(default,)]
+#[serde(transparent)]
+pub struct Binaries(pub BTreeMap<String, String>);
+
+impl Binaries {
+    /// Returns the binary for a platform such as `linux/amd64`, if there is one
+    pub fn get(&self, platform: &str) -> Option<Binary> {
+        self.0.get(platform).map(|url| Binary::parse(platform, url))
+    }
+
+    /// Returns every binary, ordered by platform
+    pub fn iter(&self) -> impl Iterator<Item = Binary> + '_ {
+        self.0
+            .iter()
+            .map(|(platform, url)| Binary::parse(platform, url))
+    }
+
+    /// Returns the binary for the platform this program was compiled for. See [`host_platform`].
+    pub fn for_host(&self) -> Option<Binary> {
+        self.get(&host_platform()?)
+    }
+}
+
+/// The registry's name for the platform this program was compiled for, e.g. `linux/amd64` or `darwin/arm64`, or
+/// `None` if the registry has no name for its OS or architecture
+pub fn host_platform() -> Option<String> {
+    platform_name(std::env::consts::OS, std::env::consts::ARCH)
+}
+
+/// Translates Rust's `target_os` and `target_arch` names into the Go names the registry uses
+fn platform_name(os: &str, arch: &str) -> Option<String> {
+    let os = match os {
+        "macos" => "darwin",
+        "linux" | "windows" | "freebsd" | "openbsd" | "netbsd" => os,
+        _ => return None,
+    };
+    let arch = match arch {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        "x86" => "386",
+        "arm" => "arm",
+        _ => return None,
+    };
+
+    Some(format!("{}/{}", os, arch))
+}
+
+/// A binary download from a [`Binaries`] entry
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Binary {
+    /// The platform the binary is built for, e.g. `linux/amd64`
+    pub platform: String,
+    /// The download URL without its `checksum` query parameter
+    pub url: String,
+    /// The checksum from the URL's `checksum` query parameter, if any
+    pub checksum: Option<Checksum>,
+}
+
+impl Binary {
+    fn parse(platform: &str, url: &str) -> Binary {
+        let (base, query) = match url.split_once('?') {
+            Some((base, query)) => (base, query),
+            None => (url, ""),
+        };
+        let mut checksum = None;
+        let mut rest = Vec::new();
+
+        for param in query.split('&').filter(|p| !p.is_empty()) {
+            match param.strip_prefix("checksum=").and_then(Checksum::parse) {
+                Some(parsed) if checksum.is_none() => checksum = Some(parsed),
+                _ => rest.push(param),
+            }
+        }
+
+        Binary {
+            platform: platform.to_string(),
+            url: if rest.is_empty() {
+                base.to_string()
+            } else {
+                format!("{}?{}", base, rest.join("&"))
+            },
+            checksum,
+        }
+    }
+}
+
+/// A digest in the `<algorithm>:<digest>` form used by the registry, e.g. `sha256:4d5b...`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Checksum {
+    /// The hash algorithm in lowercase, e.g. `sha256`
+    pub algorithm: String,
+    /// The hex-encoded digest in lowercase
+    pub digest: String,
+}
+
+impl Checksum {
+    /// Parses `<algorithm>:<digest>`, returning `None` if either part is missing
+    pub fn parse(value: &str) -> Option<Checksum> {
+        let (algorithm, digest) = value.split_once(':')?;
+        if algorithm.is_empty() || digest.is_empty() {
+            return None;
+        }
+
+        Some(Checksum {
+            algorithm: algorithm.to_ascii_lowercase(),
+            digest: digest.to_ascii_lowercase(),
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, remote = "Self")]
 pub struct Peers {
-    #[serde(skip_serializing_if = "Vec::is_empty", default = "Vec::new")]
     pub seeds: Vec<Seed>,
     pub persistent_peers: Vec<PersistentPeer>,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
-#[serde(default,)]
+#[serde(default, remote = "Self")]
 pub struct Seed {
     pub id: String,
     pub address: String,
     pub provider: Option<String>,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, remote = "Self")]
 pub struct PersistentPeer {
     pub id: String,
     pub address: String,
+    pub provider: Option<String>,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
-#[serde(default,)]
+#[serde(default, remote = "Self")]
 pub struct Apis {
-    #[serde(skip_serializing_if = "Vec::is_empty", default = "Vec::new")]
     pub rpc: Vec<Rpc>,
-    #[serde(skip_serializing_if = "Vec::is_empty", default = "Vec::new")]
     pub rest: Vec<Rest>,
     pub grpc: Vec<Grpc>,
+    pub wss: Vec<Endpoint>,
+    #[serde(rename = "grpc-web")]
+    pub grpc_web: Vec<Endpoint>,
+    #[serde(rename = "evm-http-jsonrpc")]
+    pub evm_http_jsonrpc: Vec<Endpoint>,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
-#[serde(default,)]
+#[serde(default, remote = "Self")]
 pub struct Rpc {
     pub address: String,
     pub provider: Option<String>,
+    /// Whether the node keeps the chain's full history
+    pub archive: Option<bool>,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
-#[serde(default,)]
+#[serde(default, remote = "Self")]
 pub struct Rest {
     pub address: String,
     pub provider: Option<String>,
+    /// Whether the node keeps the chain's full history
+    pub archive: Option<bool>,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
-#[serde(default,)]
+#[serde(default, remote = "Self")]
 pub struct Grpc {
     pub address: String,
     pub provider: Option<String>,
+    /// Whether the node keeps the chain's full history
+    pub archive: Option<bool>,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
-#[serde(default,)]
+#[serde(default, remote = "Self")]
+pub struct Endpoint {
+    pub address: String,
+    pub provider: Option<String>,
+    pub archive: Option<bool>,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, remote = "Self")]
 pub struct Fees {
-    #[serde(skip_serializing_if = "Vec::is_empty", default = "Vec::new")]
     pub fee_tokens: Vec<FeeToken>,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
-#[serde(default,)]
+#[serde(default, remote = "Self")]
 pub struct FeeToken {
     pub denom: String,
     pub fixed_min_gas_price: f32,
     pub low_gas_price: f32,
     pub average_gas_price: f32,
     pub high_gas_price: f32,
+    pub gas_costs: Option<GasCosts>,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
-#[serde(default,)]
+#[serde(default, remote = "Self")]
+pub struct GasCosts {
+    pub cosmos_send: Option<u64>,
+    pub ibc_transfer: Option<u64>,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, remote = "Self")]
 pub struct Staking {
-    #[serde(skip_serializing_if = "Vec::is_empty", default = "Vec::new")]
     pub staking_tokens: Vec<StakingToken>,
+    pub lock_duration: Option<LockDuration>,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
-#[serde(default,)]
+#[serde(default, remote = "Self")]
 pub struct StakingToken {
     pub denom: String,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
-#[serde(default,)]
+#[serde(default, remote = "Self")]
+pub struct LockDuration {
+    pub blocks: Option<u64>,
+    /// A duration such as `1209600s`
+    pub time: Option<String>,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, remote = "Self")]
 pub struct Explorer {
     pub kind: String,
     pub url: String,
     pub tx_page: String,
     pub account_page: String,
+    pub validator_page: Option<String>,
+    pub proposal_page: Option<String>,
+    pub block_page: Option<String>,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
+}
+
+/// The address prefixes of a chain that doesn't derive them all from `bech32_prefix`
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, remote = "Self")]
+#[serde(rename_all = "camelCase")]
+pub struct Bech32Config {
+    pub bech32_prefix_acc_addr: Option<String>,
+    pub bech32_prefix_acc_pub: Option<String>,
+    pub bech32_prefix_val_addr: Option<String>,
+    pub bech32_prefix_val_pub: Option<String>,
+    pub bech32_prefix_cons_addr: Option<String>,
+    pub bech32_prefix_cons_pub: Option<String>,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
+}
+
+/// A logo in one or more formats
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, remote = "Self")]
+pub struct ImageURIs {
+    pub png: Option<String>,
+    pub svg: Option<String>,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
+}
+
+/// An image of the chain along with how to display it
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, remote = "Self")]
+pub struct Image {
+    /// Set when the image is copied from an asset, which should be kept in sync with it
+    pub image_sync: Option<ImageSync>,
+    pub png: Option<String>,
+    pub svg: Option<String>,
+    pub theme: Option<ImageTheme>,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, remote = "Self")]
+pub struct ImageSync {
+    pub chain_name: String,
+    pub base_denom: Option<String>,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, remote = "Self")]
+pub struct ImageTheme {
+    pub primary_color_hex: Option<String>,
+    pub background_color_hex: Option<String>,
+    pub circle: Option<bool>,
+    pub dark_mode: Option<bool>,
+    pub monochrome: Option<bool>,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assay::assay;
+
+    fn codebase() -> Codebase {
+        serde_json::from_str(
+            r#"{"versions": [
+                {"name": "v1", "recommended_version": "v1.0.2"},
+                {"name": "v2", "height": 100, "proposal": 4},
+                {"name": "v3", "height": 250},
+                {"name": "v4"}
+            ]}"#,
+        )
+        .unwrap()
+    }
+
+    #[assay]
+    fn finds_version_at_height() {
+        let codebase = codebase();
+        let name = |height| codebase.version_at_height(height).map(|v| v.name.as_str());
+
+        assert_eq!(name(1), Some("v1"));
+        assert_eq!(name(99), Some("v1"));
+        assert_eq!(name(100), Some("v2"));
+        assert_eq!(name(249), Some("v2"));
+        assert_eq!(name(1_000_000), Some("v3"));
+        assert_eq!(Codebase::default().version_at_height(1), None);
+    }
+
+    #[assay]
+    fn parses_binaries() {
+        let binaries: Binaries = serde_json::from_str(
+            r#"{
+                "linux/amd64": "https://example.com/osmosisd-linux-amd64?checksum=sha256:AB12",
+                "linux/arm": "https://example.com/osmosisd-linux-arm",
+                "windows/arm64": "https://example.com/osmosisd.zip?archive=zip&checksum=sha512:cd34"
+            }"#,
+        )
+        .unwrap();
+
+        let linux = binaries.get("linux/amd64").unwrap();
+        assert_eq!(linux.url, "https://example.com/osmosisd-linux-amd64");
+        assert_eq!(
+            linux.checksum,
+            Some(Checksum {
+                algorithm: "sha256".to_string(),
+                digest: "ab12".to_string()
+            })
+        );
+        assert_eq!(binaries.get("linux/arm").unwrap().checksum, None);
+        let windows = binaries.get("windows/arm64").unwrap();
+        assert_eq!(windows.url, "https://example.com/osmosisd.zip?archive=zip");
+        assert_eq!(windows.checksum.unwrap().algorithm, "sha512");
+        assert_eq!(binaries.iter().count(), 3);
+        assert_eq!(binaries.get("darwin/arm64"), None);
+    }
+
+    #[assay]
+    fn names_host_platforms() {
+        assert_eq!(
+            platform_name("linux", "x86_64").as_deref(),
+            Some("linux/amd64")
+        );
+        assert_eq!(
+            platform_name("macos", "aarch64").as_deref(),
+            Some("darwin/arm64")
+        );
+        assert_eq!(
+            platform_name("windows", "x86").as_deref(),
+            Some("windows/386")
+        );
+        assert_eq!(platform_name("ios", "aarch64"), None);
+        assert_eq!(platform_name("linux", "riscv64"), None);
+
+        let binaries = Binaries(
+            [(
+                host_platform().unwrap(),
+                "https://example.com/d".to_string(),
+            )]
+            .into_iter()
+            .collect(),
+        );
+        assert_eq!(binaries.for_host().unwrap().url, "https://example.com/d");
+    }
+
+    #[assay]
+    fn finds_next_upgrade() {
+        let codebase = codebase();
+        let name = |height| codebase.next_upgrade_after(height).map(|v| v.name.as_str());
+
+        assert_eq!(name(0), Some("v2"));
+        assert_eq!(name(100), Some("v3"));
+        // v4 hasn't been scheduled
+        assert_eq!(name(250), None);
+        assert_eq!(codebase.versions[1].proposal, Some(4));
+    }
 }
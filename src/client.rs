@@ -0,0 +1,1399 @@
+//! A configurable client for the chain registry repository. Unlike the free functions in [`crate::get`], a
+//! [`RegistryClient`] isn't tied to a single hard-coded commit and can be pointed at any branch, tag or commit, or
+//! at a mirror of the registry.
+use crate::{
+    archive::RegistrySnapshot,
+    chain::{Binary, ChainInfo},
+    disk_cache::{is_commit_sha, DiskCache},
+    download,
+    error::{RegistryError, Result},
+    github::{git_blob_sha, Commit, Tree, TreeEntry},
+    rate_limit::{rate_limit_reset, RateLimit},
+    retry::{is_retryable_error, RetryPolicy},
+    source::{DirEntry, RegistrySource},
+};
+use async_trait::async_trait;
+use http::{
+    header::{AUTHORIZATION, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+    HeaderMap, HeaderValue, Method, StatusCode,
+};
+use reqwest::{Proxy, Response};
+use serde::de::DeserializeOwned;
+use std::{
+    collections::HashMap,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+use tokio::sync::mpsc;
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+const TREE_CACHE_KEY: &str = "tree.json";
+/// The shortest sleep before retrying a rate-limited request, in case the reported reset has already passed
+const MIN_RATE_LIMIT_WAIT: Duration = Duration::from_secs(1);
+/// How many times a request sleeps on the rate limit before failing with [`RegistryError::RateLimited`]
+const MAX_RATE_LIMIT_WAITS: u32 = 3;
+/// The most responses remembered for conditional requests, enough for every file in the registry
+const MAX_VALIDATED_RESPONSES: usize = 4096;
+
+/// The blob SHA of every file in a git tree, keyed by path
+type BlobShas = Arc<HashMap<String, String>>;
+
+/// The registry commit used by [`RegistryClient::default`]
+pub const DEFAULT_GIT_REF: &str = "350840e766f7574a120760a13eda4c466413308a";
+/// Base URL raw registry files are read from
+pub const DEFAULT_RAW_FILE_URL: &str = "https://raw.githubusercontent.com/cosmos/chain-registry";
+/// Base URL of the GitHub REST API for the registry repository
+pub const DEFAULT_API_URL: &str = "https://api.github.com/repos/cosmos/chain-registry";
+/// The maximum time a binary, genesis file or snapshot download may take, unless set with
+/// [`RegistryClient::with_download_timeout`]
+pub const DEFAULT_DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// Settings for the HTTP client a [`RegistryClient`] sends requests with
+///
+/// # Examples
+///
+/// ```ignore
+/// use chain_registry::{client::HttpConfig, RegistryClient};
+/// use std::time::Duration;
+///
+/// let config = HttpConfig {
+///     connect_timeout: Some(Duration::from_secs(5)),
+///     proxy: Some("http://proxy.internal:3128".to_string()),
+///     user_agent: "my-indexer/1.0".to_string(),
+///     ..Default::default()
+/// };
+/// let client = RegistryClient::new().with_http_config(config)?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct HttpConfig {
+    /// The maximum time to wait for a connection to be established
+    pub connect_timeout: Option<Duration>,
+    /// The maximum time a whole request may take, from connecting until the response has been read in full. This is a
+    /// deadline rather than a limit on the time between reads, so downloads of binaries, genesis files and snapshots
+    /// are bounded by [`RegistryClient::with_download_timeout`] instead. Unset by default.
+    pub request_timeout: Option<Duration>,
+    /// The URL of a proxy to send all requests through, e.g. `http://proxy.internal:3128`. When unset, the proxy is
+    /// taken from the `HTTP_PROXY` and `HTTPS_PROXY` environment variables if present.
+    pub proxy: Option<String>,
+    /// The `User-Agent` header sent with every request
+    pub user_agent: String,
+    /// Headers sent with every request
+    pub default_headers: HeaderMap,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        HttpConfig {
+            connect_timeout: Some(Duration::from_secs(10)),
+            request_timeout: None,
+            proxy: None,
+            user_agent: format!("chain-registry/{}", VERSION),
+            default_headers: HeaderMap::new(),
+        }
+    }
+}
+
+impl HttpConfig {
+    /// Builds a [`reqwest::Client`] from these settings
+    pub fn build_client(&self) -> Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder()
+            .user_agent(self.user_agent.as_str())
+            .default_headers(self.default_headers.clone());
+
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.request_timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(proxy) = &self.proxy {
+            let proxy = Proxy::all(proxy.as_str())
+                .map_err(|e| RegistryError::Config(format!("invalid proxy {}: {}", proxy, e)))?;
+            builder = builder.proxy(proxy);
+        }
+
+        builder
+            .build()
+            .map_err(|e| RegistryError::Config(format!("error building HTTP client: {}", e)))
+    }
+}
+
+/// Retrieves data from the chain registry at a configurable git ref.
+///
+/// # Examples
+///
+/// ```ignore
+/// use chain_registry::RegistryClient;
+///
+/// let client = RegistryClient::new().with_ref("master");
+/// let chain = client.get_chain("osmosis").await?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct RegistryClient {
+    git_ref: String,
+    raw_file_url: String,
+    api_url: String,
+    http: reqwest::Client,
+    authorization: Option<HeaderValue>,
+    wait_on_rate_limit: bool,
+    rate_limit: Arc<Mutex<Option<RateLimit>>>,
+    retry_policy: RetryPolicy,
+    disk_cache: Option<DiskCache>,
+    // the last successful response for each URL, shared between clones, when conditional requests are enabled
+    validated: Option<Arc<Mutex<HashMap<String, Validated>>>>,
+    verify_integrity: bool,
+    download_timeout: Duration,
+    // the blob SHAs from the most recently read tree and the ref it was read at
+    blob_shas: Arc<Mutex<Option<(String, BlobShas)>>>,
+}
+
+/// A response whose body has been read in full
+struct Fetched {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Vec<u8>,
+    // false if the body is the same as the last successful response for the URL
+    changed: bool,
+}
+
+/// The validators and body of the last successful response for a URL
+#[derive(Clone, Debug)]
+struct Validated {
+    etag: Option<HeaderValue>,
+    last_modified: Option<HeaderValue>,
+    body: Vec<u8>,
+}
+
+/// A value read by one of the `poll_*` methods of [`RegistryClient`], along with whether it changed since the last
+/// time it was read by this client or one of its clones
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Polled<T> {
+    /// The value that was read
+    pub value: T,
+    /// `false` if the content is the same as last time, including when the server answered `304 Not Modified`.
+    /// Always `true` the first time a URL is read and when conditional requests aren't enabled.
+    pub changed: bool,
+}
+
+impl Default for RegistryClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RegistryClient {
+    /// Creates a client that reads from the public GitHub registry at [`DEFAULT_GIT_REF`]
+    pub fn new() -> Self {
+        RegistryClient {
+            git_ref: DEFAULT_GIT_REF.to_string(),
+            raw_file_url: DEFAULT_RAW_FILE_URL.to_string(),
+            api_url: DEFAULT_API_URL.to_string(),
+            http: HttpConfig::default()
+                .build_client()
+                .expect("failed to build default HTTP client"),
+            authorization: None,
+            wait_on_rate_limit: false,
+            rate_limit: Arc::default(),
+            retry_policy: RetryPolicy::default(),
+            disk_cache: None,
+            validated: None,
+            verify_integrity: false,
+            download_timeout: DEFAULT_DOWNLOAD_TIMEOUT,
+            blob_shas: Arc::default(),
+        }
+    }
+
+    /// Sets the git ref (branch, tag or commit SHA) to read registry data from
+    pub fn with_ref(mut self, git_ref: impl Into<String>) -> Self {
+        self.git_ref = git_ref.into();
+        self
+    }
+
+    /// Sets the base URL raw files are read from. Files are requested from `<raw_file_url>/<ref>/<path>`.
+    pub fn with_raw_file_url(mut self, url: impl Into<String>) -> Self {
+        self.raw_file_url = url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Sets the base URL of the GitHub API for the registry repository. Directory listings are requested from
+    /// `<api_url>/git/trees/<ref>?recursive=1`.
+    pub fn with_api_url(mut self, url: impl Into<String>) -> Self {
+        self.api_url = url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Replaces the client's HTTP client with one built from `config`. The HTTP client is shared by every clone of this
+    /// client so connections are pooled between them.
+    pub fn with_http_config(mut self, config: HttpConfig) -> Result<Self> {
+        self.http = config.build_client()?;
+        Ok(self)
+    }
+
+    /// Replaces the client's HTTP client with a preconfigured [`reqwest::Client`]
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.http = client;
+        self
+    }
+
+    /// Authenticates every request with a GitHub token, raising the API rate limit from 60 to 5000 requests per hour.
+    /// The token is sent to both the raw file and API base URLs, so only set it when those point at hosts you trust.
+    pub fn with_token(mut self, token: impl AsRef<str>) -> Result<Self> {
+        let mut value = HeaderValue::from_str(&format!("Bearer {}", token.as_ref()))
+            .map_err(|_| RegistryError::Config("invalid GitHub token".to_string()))?;
+        value.set_sensitive(true);
+        self.authorization = Some(value);
+
+        Ok(self)
+    }
+
+    /// When enabled, a request rejected by GitHub's rate limit sleeps until the limit resets, and for at least a second,
+    /// and is then retried instead of failing with [`RegistryError::RateLimited`]. A request still rate limited after
+    /// three sleeps fails anyway. Disabled by default.
+    pub fn with_wait_on_rate_limit(mut self, wait: bool) -> Self {
+        self.wait_on_rate_limit = wait;
+        self
+    }
+
+    /// Sets the policy for retrying requests that fail with a timeout, connection error or transient status.
+    /// Defaults to [`RetryPolicy::default`]; use [`RetryPolicy::none`] to disable retries.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Stores file contents and directory listings in a [`DiskCache`] and serves them from there on later calls,
+    /// including from other processes sharing the directory. Only used when the client's ref is a full commit SHA, since
+    /// branches and tags can move; use [`RegistryClient::resolve_ref`] to pin a branch first.
+    pub fn with_disk_cache(mut self, cache: DiskCache) -> Self {
+        self.disk_cache = Some(cache);
+        self
+    }
+
+    /// When enabled, the client remembers the `ETag` and `Last-Modified` headers and the body of each successful
+    /// response, and sends them back as `If-None-Match` and `If-Modified-Since` when the same URL is requested again.
+    /// A `304 Not Modified` answer reuses the remembered body, which saves bandwidth when polling a branch for updates.
+    /// Use [`RegistryClient::poll_file_content`] and [`RegistryClient::poll_tree`] to find out whether the content
+    /// changed. The remembered responses are kept in memory and shared between clones of this client. Up to 4096 are
+    /// kept, beyond which an arbitrary one is forgotten for each new URL, and snapshot downloads are never remembered.
+    /// Disabled by default.
+    pub fn with_conditional_requests(mut self, enabled: bool) -> Self {
+        self.validated = enabled.then(Arc::default);
+        self
+    }
+
+    /// When enabled, every file read with [`RegistrySource::get_file_content`], including chains, asset lists and IBC
+    /// paths, is hashed as a git blob and compared with the SHA listed for it in [`RegistryClient::get_tree`]. A
+    /// mismatch fails with [`RegistryError::Integrity`], so a mirror, proxy or disk cache can't substitute different
+    /// content, and a file missing from the tree fails with [`RegistryError::NotFound`]. The tree is read once per ref
+    /// and refreshed by each call to [`RegistryClient::get_tree`] or [`RegistryClient::poll_tree`]. Because a branch
+    /// can move between reading the tree and a file, pin it with [`RegistryClient::resolve_ref`] first. Disabled by
+    /// default.
+    pub fn with_integrity_check(mut self, enabled: bool) -> Self {
+        self.verify_integrity = enabled;
+        self
+    }
+
+    /// Sets the maximum time a download started with [`RegistryClient::download_binary`],
+    /// [`RegistryClient::download_genesis`] or [`RegistryClient::download_snapshot`] may take. It replaces
+    /// [`HttpConfig::request_timeout`] for those requests, since they can be far larger than registry files. Defaults to
+    /// [`DEFAULT_DOWNLOAD_TIMEOUT`].
+    pub fn with_download_timeout(mut self, timeout: Duration) -> Self {
+        self.download_timeout = timeout;
+        self
+    }
+
+    /// The disk cache responses are stored in, if any
+    pub fn disk_cache(&self) -> Option<&DiskCache> {
+        self.disk_cache.as_ref()
+    }
+
+    /// The rate limit reported by the most recent GitHub API response, if any has been received
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        *self.rate_limit.lock().expect("rate limit lock poisoned")
+    }
+
+    /// The git ref this client reads from
+    pub fn git_ref(&self) -> &str {
+        &self.git_ref
+    }
+
+    /// The base URL raw files are read from
+    pub fn raw_file_url(&self) -> &str {
+        &self.raw_file_url
+    }
+
+    /// The base URL of the GitHub API for the registry repository
+    pub fn api_url(&self) -> &str {
+        &self.api_url
+    }
+
+    /// Sends a GET request, handling authentication and rate limiting. The response status is not checked.
+    async fn send(
+        &self,
+        url: &str,
+        previous: Option<&Validated>,
+        timeout: Option<Duration>,
+    ) -> Result<Response> {
+        let mut waits = 0;
+
+        loop {
+            let mut req = self.http.request(Method::GET, url);
+            if let Some(timeout) = timeout {
+                req = req.timeout(timeout);
+            }
+            if let Some(auth) = &self.authorization {
+                req = req.header(AUTHORIZATION, auth.clone());
+            }
+            if let Some(etag) = previous.and_then(|p| p.etag.clone()) {
+                req = req.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(modified) = previous.and_then(|p| p.last_modified.clone()) {
+                req = req.header(IF_MODIFIED_SINCE, modified);
+            }
+            let response = req.send().await?;
+
+            if let Some(rl) = RateLimit::from_headers(response.headers()) {
+                *self.rate_limit.lock().expect("rate limit lock poisoned") = Some(rl);
+            }
+
+            match rate_limit_reset(response.status(), response.headers()) {
+                Some(reset) if self.wait_on_rate_limit && waits < MAX_RATE_LIMIT_WAITS => {
+                    let wait = reset.duration_since(SystemTime::now()).unwrap_or_default();
+                    tokio::time::sleep(wait.max(MIN_RATE_LIMIT_WAIT)).await;
+                    waits += 1;
+                }
+                Some(reset) => return Err(RegistryError::RateLimited { reset }),
+                None => return Ok(response),
+            }
+        }
+    }
+
+    /// Sends a GET request and reads the response body, retrying transient failures according to the client's
+    /// [`RetryPolicy`]. When conditional requests are enabled, a `304 Not Modified` response is returned as a `200 OK`
+    /// with the previous body. The response status is otherwise not checked.
+    async fn fetch(&self, url: &str) -> Result<Fetched> {
+        let previous = self.previous_response(url);
+        let mut attempt = 1;
+
+        loop {
+            let result = match self.send(url, previous.as_ref(), None).await {
+                Ok(response) => {
+                    let status = response.status();
+                    let headers = response.headers().clone();
+                    response.bytes().await.map(|body| Fetched {
+                        status,
+                        headers,
+                        body: body.into(),
+                        changed: true,
+                    })
+                }
+                Err(RegistryError::Request(err)) => Err(err),
+                // rate limiting is handled by send() and other errors aren't transient
+                Err(err) => return Err(err),
+            };
+            let retryable = match &result {
+                Ok(fetched) => self.retry_policy.is_retryable_status(fetched.status),
+                Err(err) => is_retryable_error(err),
+            };
+
+            if !retryable || attempt >= self.retry_policy.max_attempts {
+                return Ok(self.revalidate(url, previous, result?));
+            }
+
+            tokio::time::sleep(self.retry_policy.delay(attempt)).await;
+            attempt += 1;
+        }
+    }
+
+    /// The last successful response for `url`, if conditional requests are enabled
+    fn previous_response(&self, url: &str) -> Option<Validated> {
+        let validated = self.validated.as_ref()?;
+        let validated = validated.lock().expect("conditional request lock poisoned");
+
+        validated.get(url).cloned()
+    }
+
+    /// Substitutes the previous body for a `304 Not Modified` response and remembers successful responses
+    fn revalidate(&self, url: &str, previous: Option<Validated>, mut fetched: Fetched) -> Fetched {
+        match previous {
+            Some(previous) if fetched.status == StatusCode::NOT_MODIFIED => {
+                fetched.status = StatusCode::OK;
+                fetched.body = previous.body;
+                fetched.changed = false;
+            }
+            _ if fetched.status.is_success() => {
+                fetched.changed = self.remember(
+                    url,
+                    fetched.headers.get(ETAG).cloned(),
+                    fetched.headers.get(LAST_MODIFIED).cloned(),
+                    &fetched.body,
+                );
+            }
+            _ => {}
+        }
+
+        fetched
+    }
+
+    /// Stores the body and validators of a successful response for `url`, returning whether the body differs from the
+    /// previous one. Always returns `true` when conditional requests aren't enabled.
+    fn remember(
+        &self,
+        url: &str,
+        etag: Option<HeaderValue>,
+        last_modified: Option<HeaderValue>,
+        body: &[u8],
+    ) -> bool {
+        let Some(validated) = &self.validated else {
+            return true;
+        };
+        let mut validated = validated.lock().expect("conditional request lock poisoned");
+        let changed = validated.get(url).is_none_or(|v| v.body != body);
+        if validated.len() >= MAX_VALIDATED_RESPONSES && !validated.contains_key(url) {
+            // the forgotten URL is simply requested in full next time
+            if let Some(evicted) = validated.keys().next().cloned() {
+                validated.remove(&evicted);
+            }
+        }
+        validated.insert(
+            url.to_string(),
+            Validated {
+                etag,
+                last_modified,
+                body: body.to_vec(),
+            },
+        );
+
+        changed
+    }
+
+    /// Like [`RegistryClient::fetch`], but fails unless the response status indicates success
+    async fn fetch_ok(&self, url: &str) -> Result<Fetched> {
+        let fetched = self.fetch(url).await?;
+
+        if fetched.status == StatusCode::NOT_FOUND {
+            return Err(RegistryError::NotFound {
+                path: url.to_string(),
+            });
+        }
+        if !fetched.status.is_success() {
+            return Err(RegistryError::Http {
+                url: url.to_string(),
+                status: fetched.status,
+            });
+        }
+
+        Ok(fetched)
+    }
+
+    /// Sends a GET request for a download bounded by the client's download timeout, retrying transient failures until
+    /// the response headers arrive. Fails unless the response status indicates success.
+    async fn send_download(&self, url: &str) -> Result<Response> {
+        let mut attempt = 1;
+
+        let response = loop {
+            let result = self.send(url, None, Some(self.download_timeout)).await;
+            let retryable = match &result {
+                Ok(response) => self.retry_policy.is_retryable_status(response.status()),
+                Err(RegistryError::Request(err)) => is_retryable_error(err),
+                Err(_) => false,
+            };
+
+            if !retryable || attempt >= self.retry_policy.max_attempts {
+                break result?;
+            }
+
+            tokio::time::sleep(self.retry_policy.delay(attempt)).await;
+            attempt += 1;
+        };
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(RegistryError::NotFound {
+                path: url.to_string(),
+            });
+        }
+        if !response.status().is_success() {
+            return Err(RegistryError::Http {
+                url: url.to_string(),
+                status: response.status(),
+            });
+        }
+
+        Ok(response)
+    }
+
+    /// The disk cache, if one is configured and the client's ref can be cached
+    fn commit_cache(&self) -> Option<&DiskCache> {
+        self.disk_cache
+            .as_ref()
+            .filter(|_| is_commit_sha(&self.git_ref))
+    }
+
+    async fn read_cached(&self, key: &str) -> Option<Vec<u8>> {
+        self.commit_cache()?.get(&self.git_ref, key).await
+    }
+
+    async fn write_cached(&self, key: &str, data: &[u8]) {
+        if let Some(cache) = self.commit_cache() {
+            // the response is still good if it can't be cached
+            let _ = cache.put(&self.git_ref, key, data).await;
+        }
+    }
+
+    /// Sends a GET request to the GitHub API and deserializes the JSON response
+    async fn get_json<T: DeserializeOwned>(&self, url: String) -> Result<T> {
+        let body = self.fetch_ok(&url).await?.body;
+
+        serde_json::from_slice(&body).map_err(|e| RegistryError::parse(url, e))
+    }
+
+    /// Lists every file and directory in the registry at this client's ref, along with each blob's SHA and size, using
+    /// the recursive git trees API. Unlike the contents API, which stops at 1000 entries per directory, this returns
+    /// the complete tree in one request. An error is returned if GitHub truncates the response.
+    pub async fn get_tree(&self) -> Result<Vec<TreeEntry>> {
+        Ok(self.poll_tree().await?.value)
+    }
+
+    /// Like [`RegistryClient::get_tree`], but also reports whether the tree changed since this client last read it.
+    /// With [`RegistryClient::with_conditional_requests`] enabled, an unchanged tree costs a `304 Not Modified`
+    /// response rather than a full download.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use chain_registry::RegistryClient;
+    ///
+    /// let client = RegistryClient::new().with_ref("master").with_conditional_requests(true);
+    /// loop {
+    ///     if client.poll_tree().await?.changed {
+    ///         // reload the registry
+    ///     }
+    ///     tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+    /// }
+    /// ```
+    pub async fn poll_tree(&self) -> Result<Polled<Vec<TreeEntry>>> {
+        let url = format!("{}/git/trees/{}?recursive=1", self.api_url, self.git_ref);
+        let cached = self.read_cached(TREE_CACHE_KEY).await;
+        let (body, changed) = match &cached {
+            Some(body) => (body.clone(), self.remember(&url, None, None, body)),
+            None => {
+                let fetched = self.fetch_ok(&url).await?;
+                (fetched.body, fetched.changed)
+            }
+        };
+        let tree: Tree =
+            serde_json::from_slice(&body).map_err(|e| RegistryError::parse(url.clone(), e))?;
+
+        if tree.truncated {
+            return Err(RegistryError::InvalidResponse {
+                url,
+                reason: format!(
+                    "git tree was truncated by the GitHub API after {} entries",
+                    tree.tree.len()
+                ),
+            });
+        }
+        if cached.is_none() {
+            self.write_cached(TREE_CACHE_KEY, &body).await;
+        }
+        if self.verify_integrity {
+            self.store_blob_shas(&tree.tree);
+        }
+
+        Ok(Polled {
+            value: tree.tree,
+            changed,
+        })
+    }
+
+    /// Like [`RegistrySource::get_file_content`], but also reports whether the file changed since this client last
+    /// read it. With [`RegistryClient::with_conditional_requests`] enabled, an unchanged file costs a
+    /// `304 Not Modified` response rather than a full download.
+    pub async fn poll_file_content(&self, path: &str) -> Result<Polled<String>> {
+        let key = format!("files/{}", path);
+        let url = format!("{}/{}/{}", self.raw_file_url, self.git_ref, path);
+        let cached = self.read_cached(&key).await;
+        let (body, changed) = match &cached {
+            Some(data) => (data.clone(), self.remember(&url, None, None, data)),
+            None => {
+                let fetched = self.fetch(&url).await?;
+
+                if fetched.status == StatusCode::NOT_FOUND {
+                    return Err(RegistryError::NotFound {
+                        path: path.to_string(),
+                    });
+                }
+                if !fetched.status.is_success() {
+                    return Err(RegistryError::Http {
+                        url,
+                        status: fetched.status,
+                    });
+                }
+
+                (fetched.body, fetched.changed)
+            }
+        };
+
+        self.verify_blob(path, &body).await?;
+        if cached.is_none() {
+            self.write_cached(&key, &body).await;
+        }
+
+        Ok(Polled {
+            value: String::from_utf8_lossy(&body).into_owned(),
+            changed,
+        })
+    }
+
+    /// Checks `data` against the blob SHA listed for `path` in the tree, if integrity checks are enabled
+    async fn verify_blob(&self, path: &str, data: &[u8]) -> Result<()> {
+        if !self.verify_integrity {
+            return Ok(());
+        }
+
+        let shas = self.blob_shas().await?;
+        let expected = shas.get(path).ok_or_else(|| RegistryError::NotFound {
+            path: path.to_string(),
+        })?;
+        let actual = git_blob_sha(data);
+
+        if actual != *expected {
+            return Err(RegistryError::Integrity {
+                path: path.to_string(),
+                expected: expected.clone(),
+                actual,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// The blob SHAs of the tree at this client's ref, reading the tree if it hasn't been read yet
+    async fn blob_shas(&self) -> Result<BlobShas> {
+        let stored = self
+            .blob_shas
+            .lock()
+            .expect("blob SHA lock poisoned")
+            .clone();
+        if let Some((git_ref, shas)) = stored {
+            if git_ref == self.git_ref {
+                return Ok(shas);
+            }
+        }
+
+        let tree = self.get_tree().await?;
+
+        Ok(self.store_blob_shas(&tree))
+    }
+
+    fn store_blob_shas(&self, tree: &[TreeEntry]) -> BlobShas {
+        let shas: BlobShas = Arc::new(
+            tree.iter()
+                .filter(|e| e.type_field == "blob")
+                .map(|e| (e.path.clone(), e.sha.clone()))
+                .collect(),
+        );
+        *self.blob_shas.lock().expect("blob SHA lock poisoned") =
+            Some((self.git_ref.clone(), shas.clone()));
+
+        shas
+    }
+
+    /// Downloads a chain binary into `dir`, streaming it to disk. If the registry declares a checksum for the binary,
+    /// the download is verified against it and fails with [`RegistryError::Integrity`] on a mismatch, leaving nothing
+    /// behind. `.tar.gz`, `.tgz` and `.zip` downloads are unpacked into `dir`; anything else is saved under the last
+    /// segment of its URL and made executable. Returns the paths of the files written.
+    ///
+    /// Binaries are hosted outside the registry, so the request is sent without the client's GitHub token.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use chain_registry::{RegistryClient, RegistrySource};
+    ///
+    /// let client = RegistryClient::new();
+    /// let chain = client.get_chain("osmosis").await?.unwrap();
+    /// if let Some(binary) = chain.codebase.binaries.for_host() {
+    ///     let files = client.download_binary(&binary, "/opt/osmosis/bin").await?;
+    /// }
+    /// ```
+    pub async fn download_binary(
+        &self,
+        binary: &Binary,
+        dir: impl AsRef<Path>,
+    ) -> Result<Vec<PathBuf>> {
+        download::download_binary(&self.http, self.download_timeout, binary, dir.as_ref()).await
+    }
+
+    /// Downloads the genesis file of `chain` to `dest`, streaming it to disk. Gzipped genesis files are decompressed
+    /// and tarballs are searched for the first JSON file. If the chain declares a `genesis_sha256`, the genesis is
+    /// verified against it and fails with [`RegistryError::Integrity`] on a mismatch. The genesis `chain_id` must match
+    /// [`ChainInfo::chain_id`], otherwise [`RegistryError::InvalidResponse`] is returned. `dest` is only written once
+    /// every check has passed.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use chain_registry::{RegistryClient, RegistrySource};
+    ///
+    /// let client = RegistryClient::new();
+    /// let chain = client.get_chain("osmosis").await?.unwrap();
+    /// client.download_genesis(&chain, "/root/.osmosisd/config/genesis.json").await?;
+    /// ```
+    pub async fn download_genesis(&self, chain: &ChainInfo, dest: impl AsRef<Path>) -> Result<()> {
+        download::download_genesis(&self.http, self.download_timeout, chain, dest.as_ref()).await
+    }
+
+    /// Downloads the registry at this client's ref as a single tarball from the GitHub API and parses every chain,
+    /// asset list and IBC path out of it. This is much faster than requesting each file individually when most of the
+    /// registry is needed. The tarball is parsed as it's streamed, so it's never held in memory in full, and the
+    /// download is bounded by [`RegistryClient::with_download_timeout`].
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use chain_registry::RegistryClient;
+    ///
+    /// let snapshot = RegistryClient::default().download_snapshot().await?;
+    /// let osmosis = &snapshot.chains["osmosis"];
+    /// ```
+    pub async fn download_snapshot(&self) -> Result<RegistrySnapshot> {
+        let url = format!("{}/tarball/{}", self.api_url, self.git_ref);
+        let mut response = self.send_download(&url).await?;
+
+        // the archive is read on the blocking thread pool while chunks are passed to it from here
+        let (tx, rx) = mpsc::channel(16);
+        let parse = download::run_blocking(move || {
+            RegistrySnapshot::from_tar_gz(ChunkReader {
+                rx,
+                chunk: io::Cursor::default(),
+            })
+        });
+        while let Some(chunk) = response.chunk().await? {
+            // the parser stopped early and will report why
+            if tx.send(chunk.into()).await.is_err() {
+                break;
+            }
+        }
+        drop(tx);
+
+        parse.await
+    }
+
+    /// Resolves this client's ref (a branch, tag or commit SHA) to the commit it currently points at using the GitHub
+    /// commits API, and returns a copy of the client pinned to that commit's SHA. Every request made through the returned
+    /// client reads from the same snapshot of the registry, even if the branch moves in the meantime.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use chain_registry::RegistryClient;
+    ///
+    /// let client = RegistryClient::new().with_ref("master").resolve_ref().await?;
+    ///
+    /// // record the commit the data came from so it can be reproduced later
+    /// println!("reading registry at {}", client.git_ref());
+    /// ```
+    pub async fn resolve_ref(&self) -> Result<RegistryClient> {
+        let sha = self.resolve_commit_sha().await?;
+
+        Ok(self.clone().with_ref(sha))
+    }
+
+    /// Resolves this client's ref to the SHA of the commit it currently points at
+    pub async fn resolve_commit_sha(&self) -> Result<String> {
+        let url = format!("{}/commits/{}", self.api_url, self.git_ref);
+        let commit: Commit = self.get_json(url.clone()).await?;
+
+        if commit.sha.is_empty() {
+            return Err(RegistryError::InvalidResponse {
+                url,
+                reason: format!("no commit SHA returned for ref {}", self.git_ref),
+            });
+        }
+
+        Ok(commit.sha)
+    }
+}
+
+#[async_trait]
+impl RegistrySource for RegistryClient {
+    async fn list_dir(&self, dir: &str) -> Result<Vec<DirEntry>> {
+        Ok(tree_dir_entries(&self.get_tree().await?, dir))
+    }
+
+    async fn get_file_content(&self, path: &str) -> Result<String> {
+        Ok(self.poll_file_content(path).await?.value)
+    }
+}
+
+/// Reads a response body from chunks sent by the async runtime, blocking until each one arrives
+struct ChunkReader {
+    rx: mpsc::Receiver<Vec<u8>>,
+    chunk: io::Cursor<Vec<u8>>,
+}
+
+impl Read for ChunkReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let read = self.chunk.read(buf)?;
+            if read > 0 || buf.is_empty() {
+                return Ok(read);
+            }
+            match self.rx.blocking_recv() {
+                Some(chunk) => self.chunk = io::Cursor::new(chunk),
+                None => return Ok(0),
+            }
+        }
+    }
+}
+
+/// Selects the direct children of `dir` from a recursive tree listing
+fn tree_dir_entries(tree: &[TreeEntry], dir: &str) -> Vec<DirEntry> {
+    let prefix = if dir.is_empty() {
+        String::new()
+    } else {
+        format!("{}/", dir.trim_end_matches('/'))
+    };
+
+    tree.iter()
+        .filter_map(|e| {
+            let name = e.path.strip_prefix(&prefix)?;
+            if name.is_empty() || name.contains('/') {
+                return None;
+            }
+
+            match e.type_field.as_str() {
+                "tree" => Some(DirEntry::dir(name).with_sha(&e.sha)),
+                "blob" => {
+                    let entry = DirEntry::file(name).with_sha(&e.sha);
+                    Some(match e.size {
+                        Some(size) => entry.with_size(size),
+                        None => entry,
+                    })
+                }
+                // submodules
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        archive::tests::fixture_tar_gz,
+        test_server::{Response, TestServer},
+    };
+    use assay::assay;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn fast_retries() -> RetryPolicy {
+        RetryPolicy {
+            base_delay: Duration::from_millis(1),
+            ..Default::default()
+        }
+    }
+
+    #[assay]
+    fn builder_methods_set_urls() {
+        let client = RegistryClient::new()
+            .with_ref("master")
+            .with_raw_file_url("http://localhost:8080/raw/")
+            .with_api_url("http://localhost:8080/api/");
+
+        assert_eq!(client.git_ref(), "master");
+        assert_eq!(client.raw_file_url(), "http://localhost:8080/raw");
+        assert_eq!(client.api_url(), "http://localhost:8080/api");
+    }
+
+    #[assay]
+    async fn retries_transient_failures() {
+        let count = AtomicUsize::new(0);
+        let server = TestServer::start(move |_| {
+            if count.fetch_add(1, Ordering::SeqCst) < 2 {
+                Response::new(503, "unavailable")
+            } else {
+                Response::new(200, "{}")
+            }
+        });
+        let client = RegistryClient::new()
+            .with_raw_file_url(server.url())
+            .with_retry_policy(fast_retries());
+
+        let content = client.get_file_content("osmosis/chain.json").await.unwrap();
+
+        assert_eq!(content, "{}");
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[assay]
+    async fn gives_up_after_max_attempts() {
+        let server = TestServer::start(|_| Response::new(502, "bad gateway"));
+        let client = RegistryClient::new()
+            .with_raw_file_url(server.url())
+            .with_retry_policy(fast_retries());
+
+        assert!(client.get_file_content("osmosis/chain.json").await.is_err());
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[assay]
+    async fn does_not_retry_not_found() {
+        let server = TestServer::start(|_| Response::new(404, "not found"));
+        let client = RegistryClient::new()
+            .with_raw_file_url(server.url())
+            .with_retry_policy(fast_retries());
+
+        let err = client
+            .get_file_content("fake/chain.json")
+            .await
+            .unwrap_err();
+
+        assert!(err.is_not_found());
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[assay]
+    async fn sends_token() {
+        let server = TestServer::start(|_| Response::new(200, "{}"));
+        let client = RegistryClient::new()
+            .with_raw_file_url(server.url())
+            .with_token("ghp_secret")
+            .unwrap();
+
+        client.get_file_content("osmosis/chain.json").await.unwrap();
+
+        let requests = server.requests();
+        assert_eq!(
+            requests[0].path,
+            format!("/{}/osmosis/chain.json", DEFAULT_GIT_REF)
+        );
+        assert_eq!(
+            requests[0].header("authorization"),
+            Some("Bearer ghp_secret")
+        );
+    }
+
+    #[assay]
+    async fn serves_files_and_listings_from_disk_cache() {
+        let server = TestServer::start(|req| {
+            if req.path.contains("/git/trees/") {
+                Response::new(
+                    200,
+                    r#"{"sha": "x", "tree": [{"path": "osmosis", "type": "tree", "sha": "y"}]}"#,
+                )
+            } else {
+                Response::new(200, r#"{"chain_name": "osmosis"}"#)
+            }
+        });
+        let dir = tempfile::tempdir().unwrap();
+        let client = || {
+            RegistryClient::new()
+                .with_raw_file_url(server.url())
+                .with_api_url(server.url())
+                .with_disk_cache(DiskCache::new(dir.path()))
+        };
+
+        client()
+            .get_file_content("osmosis/chain.json")
+            .await
+            .unwrap();
+        client().list_chains().await.unwrap();
+        // a new client sharing the directory doesn't send any requests
+        let chain = client().get_chain("osmosis").await.unwrap().unwrap();
+        let chains = client().list_chains().await.unwrap();
+
+        assert_eq!(chain.chain_name, "osmosis");
+        assert_eq!(chains, vec!["osmosis"]);
+        assert_eq!(server.requests().len(), 2);
+
+        // branches can move so they aren't cached
+        let branch = client().with_ref("master");
+        branch.get_file_content("osmosis/chain.json").await.unwrap();
+        branch.get_file_content("osmosis/chain.json").await.unwrap();
+        assert_eq!(server.requests().len(), 4);
+    }
+
+    /// Serves `osmosis/chain.json` with `content`, listed in the tree with the blob SHA of `listed`
+    fn integrity_server(content: &'static str, listed: &'static str) -> TestServer {
+        TestServer::start(move |req| {
+            if req.path.contains("/git/trees/") {
+                Response::new(
+                    200,
+                    format!(
+                        r#"{{"sha": "x", "tree": [{{"path": "osmosis/chain.json", "type": "blob", "sha": "{}"}}]}}"#,
+                        git_blob_sha(listed.as_bytes())
+                    ),
+                )
+            } else {
+                Response::new(200, content)
+            }
+        })
+    }
+
+    #[assay]
+    async fn verifies_blob_shas() {
+        let server = integrity_server(
+            r#"{"chain_name": "osmosis"}"#,
+            r#"{"chain_name": "osmosis"}"#,
+        );
+        let client = RegistryClient::new()
+            .with_raw_file_url(server.url())
+            .with_api_url(server.url())
+            .with_integrity_check(true);
+
+        let chain = client.get_chain("osmosis").await.unwrap().unwrap();
+        client.get_file_content("osmosis/chain.json").await.unwrap();
+        let err = client
+            .get_file_content("juno/chain.json")
+            .await
+            .unwrap_err();
+
+        assert_eq!(chain.chain_name, "osmosis");
+        assert!(err.is_not_found());
+        // the tree is only read once
+        let trees = server
+            .requests()
+            .iter()
+            .filter(|r| r.path.contains("/git/trees/"))
+            .count();
+        assert_eq!(trees, 1);
+    }
+
+    #[assay]
+    async fn rejects_tampered_files() {
+        let server = integrity_server(r#"{"chain_name": "evil"}"#, r#"{"chain_name": "osmosis"}"#);
+        let client = RegistryClient::new()
+            .with_raw_file_url(server.url())
+            .with_api_url(server.url())
+            .with_integrity_check(true);
+
+        let err = client.get_chain("osmosis").await.unwrap_err();
+
+        match err {
+            RegistryError::Integrity {
+                path,
+                expected,
+                actual,
+            } => {
+                assert_eq!(path, "osmosis/chain.json");
+                assert_eq!(expected, git_blob_sha(br#"{"chain_name": "osmosis"}"#));
+                assert_eq!(actual, git_blob_sha(br#"{"chain_name": "evil"}"#));
+            }
+            err => panic!("unexpected error {}", err),
+        }
+        // not checked unless enabled
+        let unchecked = client.with_integrity_check(false);
+        assert!(unchecked.get_chain("osmosis").await.is_ok());
+    }
+
+    #[assay]
+    async fn sends_conditional_requests() {
+        let server = TestServer::start(|req| match req.header("if-none-match") {
+            Some("\"v1\"") => Response::new(304, ""),
+            _ => Response::new(200, r#"{"chain_name": "osmosis"}"#).with_header("ETag", "\"v1\""),
+        });
+        let client = RegistryClient::new()
+            .with_ref("master")
+            .with_raw_file_url(server.url())
+            .with_conditional_requests(true);
+
+        let first = client
+            .poll_file_content("osmosis/chain.json")
+            .await
+            .unwrap();
+        // clones share the remembered responses
+        let second = client
+            .clone()
+            .poll_file_content("osmosis/chain.json")
+            .await
+            .unwrap();
+
+        assert!(first.changed);
+        assert!(!second.changed);
+        assert_eq!(second.value, first.value);
+        let requests = server.requests();
+        assert_eq!(requests[0].header("if-none-match"), None);
+        assert_eq!(requests[1].header("if-none-match"), Some("\"v1\""));
+    }
+
+    #[assay]
+    async fn reports_changes_with_last_modified() {
+        let count = AtomicUsize::new(0);
+        let server = TestServer::start(move |req| {
+            let n = count.fetch_add(1, Ordering::SeqCst);
+            match req.header("if-modified-since") {
+                Some(_) if n < 2 => Response::new(304, ""),
+                _ => Response::new(200, format!(r#"{{"sha": "{}", "tree": []}}"#, n))
+                    .with_header("Last-Modified", "Wed, 21 Oct 2026 07:28:00 GMT"),
+            }
+        });
+        let client = RegistryClient::new()
+            .with_ref("master")
+            .with_api_url(server.url())
+            .with_conditional_requests(true);
+
+        assert!(client.poll_tree().await.unwrap().changed);
+        assert!(!client.poll_tree().await.unwrap().changed);
+        assert!(client.poll_tree().await.unwrap().changed);
+
+        let requests = server.requests();
+        assert_eq!(requests[0].header("if-modified-since"), None);
+        assert_eq!(
+            requests[1].header("if-modified-since"),
+            Some("Wed, 21 Oct 2026 07:28:00 GMT")
+        );
+    }
+
+    #[assay]
+    async fn conditional_requests_are_disabled_by_default() {
+        let server = TestServer::start(|_| Response::new(200, "{}").with_header("ETag", "\"v1\""));
+        let client = RegistryClient::new().with_raw_file_url(server.url());
+
+        client.get_file_content("osmosis/chain.json").await.unwrap();
+        let polled = client
+            .poll_file_content("osmosis/chain.json")
+            .await
+            .unwrap();
+
+        assert!(polled.changed);
+        assert_eq!(server.requests()[1].header("if-none-match"), None);
+    }
+
+    #[assay]
+    fn bounds_remembered_responses() {
+        let client = RegistryClient::new().with_conditional_requests(true);
+
+        for i in 0..MAX_VALIDATED_RESPONSES + 10 {
+            client.remember(&format!("https://example.com/{}", i), None, None, b"{}");
+        }
+
+        let validated = client.validated.as_ref().unwrap().lock().unwrap();
+        assert_eq!(validated.len(), MAX_VALIDATED_RESPONSES);
+    }
+
+    #[assay]
+    async fn snapshot_downloads_are_not_remembered() {
+        let archive = fixture_tar_gz();
+        let server = TestServer::start(move |_| {
+            Response::new(200, archive.clone()).with_header("ETag", "\"v1\"")
+        });
+        let client = RegistryClient::new()
+            .with_api_url(server.url())
+            .with_conditional_requests(true);
+
+        client.download_snapshot().await.unwrap();
+
+        assert!(client
+            .validated
+            .as_ref()
+            .unwrap()
+            .lock()
+            .unwrap()
+            .is_empty());
+    }
+
+    #[assay]
+    async fn stops_waiting_on_a_stale_rate_limit() {
+        // the reset time has already passed but the limit is still reported as exhausted
+        let server = TestServer::start(|_| {
+            Response::new(403, "rate limited")
+                .with_header("X-RateLimit-Remaining", "0")
+                .with_header("X-RateLimit-Reset", "1")
+        });
+        let client = RegistryClient::new()
+            .with_api_url(server.url())
+            .with_wait_on_rate_limit(true);
+
+        let err = client.list_chains().await.unwrap_err();
+
+        assert!(matches!(err, RegistryError::RateLimited { .. }));
+        assert_eq!(server.requests().len(), MAX_RATE_LIMIT_WAITS as usize + 1);
+    }
+
+    #[assay]
+    async fn rate_limited_request_errors() {
+        let server = TestServer::start(|_| {
+            Response::new(403, "rate limited")
+                .with_header("X-RateLimit-Limit", "60")
+                .with_header("X-RateLimit-Remaining", "0")
+                .with_header("X-RateLimit-Reset", "4102444800")
+        });
+        let client = RegistryClient::new().with_api_url(server.url());
+
+        let err = client.list_chains().await.unwrap_err();
+
+        assert!(matches!(err, RegistryError::RateLimited { .. }));
+        assert_eq!(client.rate_limit().unwrap().remaining, Some(0));
+    }
+
+    #[assay]
+    async fn sends_configured_headers() {
+        let server = TestServer::start(|_| Response::new(200, "{}"));
+        let mut default_headers = HeaderMap::new();
+        default_headers.insert("x-team", HeaderValue::from_static("indexer"));
+        let config = HttpConfig {
+            user_agent: "my-indexer/1.0".to_string(),
+            default_headers,
+            ..Default::default()
+        };
+        let client = RegistryClient::new()
+            .with_raw_file_url(server.url())
+            .with_http_config(config)
+            .unwrap();
+
+        client.get_file_content("osmosis/chain.json").await.unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests[0].header("user-agent"), Some("my-indexer/1.0"));
+        assert_eq!(requests[0].header("x-team"), Some("indexer"));
+    }
+
+    #[assay]
+    async fn sends_requests_through_proxy() {
+        // a proxy receives the absolute URL of the target in the request line
+        let proxy = TestServer::start(|_| Response::new(200, "{}"));
+        let config = HttpConfig {
+            proxy: Some(proxy.url()),
+            ..Default::default()
+        };
+        let client = RegistryClient::new()
+            .with_raw_file_url("http://registry.invalid")
+            .with_http_config(config)
+            .unwrap();
+
+        client.get_file_content("osmosis/chain.json").await.unwrap();
+
+        assert_eq!(
+            proxy.requests()[0].path,
+            format!(
+                "http://registry.invalid/{}/osmosis/chain.json",
+                DEFAULT_GIT_REF
+            )
+        );
+    }
+
+    #[assay]
+    fn invalid_proxy_errors() {
+        let config = HttpConfig {
+            proxy: Some("not a url".to_string()),
+            ..Default::default()
+        };
+
+        assert!(RegistryClient::new().with_http_config(config).is_err());
+    }
+
+    #[assay]
+    fn token_is_not_printed() {
+        let client = RegistryClient::new().with_token("ghp_secret").unwrap();
+
+        assert!(!format!("{:?}", client).contains("ghp_secret"));
+        assert!(RegistryClient::new().with_token("bad\ntoken").is_err());
+    }
+
+    #[assay]
+    fn default_client_uses_pinned_ref() {
+        let client = RegistryClient::default();
+
+        assert_eq!(client.git_ref(), DEFAULT_GIT_REF);
+        assert_eq!(client.raw_file_url(), DEFAULT_RAW_FILE_URL);
+        assert_eq!(client.api_url(), DEFAULT_API_URL);
+    }
+
+    #[assay]
+    fn deserializes_commit() {
+        let json = r#"{"sha": "350840e766f7574a120760a13eda4c466413308a", "node_id": "abc", "commit": {}}"#;
+        let commit: Commit = serde_json::from_str(json).unwrap();
+
+        assert_eq!(commit.sha, DEFAULT_GIT_REF);
+    }
+
+    #[assay]
+    fn selects_dir_entries_from_tree() {
+        let json = r#"{
+            "sha": "350840e766f7574a120760a13eda4c466413308a",
+            "tree": [
+                {"path": ".github", "mode": "040000", "type": "tree", "sha": "a1"},
+                {"path": "_IBC", "mode": "040000", "type": "tree", "sha": "a2"},
+                {"path": "_IBC/cosmoshub-osmosis.json", "mode": "100644", "type": "blob", "sha": "a3", "size": 732},
+                {"path": "cosmoshub", "mode": "040000", "type": "tree", "sha": "a4"},
+                {"path": "cosmoshub/chain.json", "mode": "100644", "type": "blob", "sha": "a5", "size": 4096},
+                {"path": "cosmoshub/images", "mode": "040000", "type": "tree", "sha": "a6"},
+                {"path": "cosmoshub/images/atom.png", "mode": "100644", "type": "blob", "sha": "a7", "size": 10}
+            ],
+            "truncated": false
+        }"#;
+        let tree: Tree = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            tree_dir_entries(&tree.tree, ""),
+            vec![
+                DirEntry::dir(".github").with_sha("a1"),
+                DirEntry::dir("_IBC").with_sha("a2"),
+                DirEntry::dir("cosmoshub").with_sha("a4"),
+            ]
+        );
+        assert_eq!(
+            tree_dir_entries(&tree.tree, "_IBC"),
+            vec![DirEntry::file("cosmoshub-osmosis.json")
+                .with_sha("a3")
+                .with_size(732)]
+        );
+        assert_eq!(
+            tree_dir_entries(&tree.tree, "cosmoshub"),
+            vec![
+                DirEntry::file("chain.json").with_sha("a5").with_size(4096),
+                DirEntry::dir("images").with_sha("a6"),
+            ]
+        );
+    }
+
+    #[assay]
+    async fn resolves_branch_to_commit() {
+        let server = TestServer::start(|_| {
+            Response::new(200, format!(r#"{{"sha":"{}"}}"#, DEFAULT_GIT_REF))
+        });
+        let client = RegistryClient::new()
+            .with_api_url(server.url())
+            .with_ref("master");
+
+        let pinned = client.resolve_ref().await.unwrap();
+
+        assert_eq!(pinned.git_ref(), DEFAULT_GIT_REF);
+        assert_eq!(server.requests()[0].path, "/commits/master");
+    }
+
+    #[assay]
+    async fn downloads_snapshot() {
+        let archive = fixture_tar_gz();
+        let server = TestServer::start(move |_| Response::new(200, archive.clone()));
+        let client = RegistryClient::new().with_api_url(server.url());
+
+        let snapshot = client.download_snapshot().await.unwrap();
+
+        assert_eq!(snapshot.chains["cosmoshub"].chain_id, "cosmoshub-4");
+        assert!(snapshot.paths.contains_key("cosmoshub-osmosis"));
+        assert_eq!(
+            server.requests()[0].path,
+            format!("/tarball/{}", DEFAULT_GIT_REF)
+        );
+    }
+
+    #[assay]
+    async fn gets_chain_at_ref() {
+        let chain = include_str!("../tests/fixtures/registry/cosmoshub/chain.json");
+        let server = TestServer::start(move |_| Response::new(200, chain));
+        let client = RegistryClient::new()
+            .with_raw_file_url(server.url())
+            .with_ref("master");
+
+        let result = client.get_chain("cosmoshub").await.unwrap().unwrap();
+
+        assert_eq!(result.chain_name, "cosmoshub");
+        assert_eq!(server.requests()[0].path, "/master/cosmoshub/chain.json");
+    }
+}
@@ -0,0 +1,486 @@
+//! Checks registry files against this crate's models. The models default every field so that a file with a missing
+//! or malformed value still parses, which also means schema drift in the registry goes unnoticed. Parsing with
+//! diagnostics reports each unknown field, type mismatch and defaulted required field along with its JSON path, e.g.
+//! `apis.rpc[3].provider`.
+use crate::{
+    assets::AssetList,
+    chain::ChainInfo,
+    error::{RegistryError, Result},
+    paths::IBCPath,
+    serde_util::{Strict, UNKNOWN_FIELD_ERROR},
+};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::fmt;
+
+/// How [`parse`] treats a file that doesn't match its model
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Fail with [`RegistryError::Schema`] if there are any diagnostics
+    Strict,
+    /// Keep unknown fields in the models' extras, default mismatched and missing ones, and return the diagnostics as
+    /// warnings
+    #[default]
+    Lenient,
+}
+
+/// What's wrong with the value at a [`Diagnostic`]'s path
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DiagnosticKind {
+    /// The model has no field for this key, so its value is only kept in the model's `extras`
+    UnknownField,
+    /// The value has the wrong type for the model and was replaced with the default. Contains the error message.
+    TypeMismatch(String),
+    /// A field the registry schema requires is missing and was defaulted
+    MissingField,
+}
+
+/// A problem found while parsing a registry file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The JSON path of the offending value, e.g. `apis.rpc[3].provider`
+    pub path: String,
+    pub kind: DiagnosticKind,
+}
+
+impl Diagnostic {
+    fn at(at: &[Segment], kind: DiagnosticKind) -> Self {
+        Diagnostic {
+            path: format_path(at),
+            kind,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            DiagnosticKind::UnknownField => write!(f, "{}: unknown field", self.path),
+            DiagnosticKind::TypeMismatch(msg) => write!(f, "{}: {}", self.path, msg),
+            DiagnosticKind::MissingField => write!(f, "{}: missing required field", self.path),
+        }
+    }
+}
+
+/// A parsed registry file along with any problems found in it
+#[derive(Clone, Debug)]
+pub struct Parsed<T> {
+    pub value: T,
+    /// Empty if the file matched the model exactly
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Models whose registry schema marks some fields as required. Each entry is a dotted path where `[]` matches every
+/// element of an array, e.g. `apis.rpc[].address`. A required field is only checked if its parent is present.
+pub trait RequiredFields {
+    const REQUIRED_FIELDS: &'static [&'static str];
+}
+
+impl RequiredFields for ChainInfo {
+    const REQUIRED_FIELDS: &'static [&'static str] = &[
+        "chain_name",
+        "chain_id",
+        "bech32_prefix",
+        "slip44",
+        "genesis.genesis_url",
+        "peers.seeds[].id",
+        "peers.seeds[].address",
+        "peers.persistent_peers[].id",
+        "peers.persistent_peers[].address",
+        "apis.rpc[].address",
+        "apis.rest[].address",
+        "apis.grpc[].address",
+        "fees.fee_tokens",
+        "fees.fee_tokens[].denom",
+        "staking.staking_tokens",
+        "staking.staking_tokens[].denom",
+    ];
+}
+
+impl RequiredFields for AssetList {
+    const REQUIRED_FIELDS: &'static [&'static str] = &[
+        "chain_name",
+        "assets",
+        "assets[].denom_units",
+        "assets[].denom_units[].denom",
+        "assets[].denom_units[].exponent",
+        "assets[].base",
+        "assets[].name",
+        "assets[].display",
+        "assets[].symbol",
+    ];
+}
+
+impl RequiredFields for IBCPath {
+    const REQUIRED_FIELDS: &'static [&'static str] = &[
+        "chain_1",
+        "chain_1.chain_name",
+        "chain_1.client_id",
+        "chain_1.connection_id",
+        "chain_2",
+        "chain_2.chain_name",
+        "chain_2.client_id",
+        "chain_2.connection_id",
+        "channels",
+        "channels[].chain_1",
+        "channels[].chain_1.channel_id",
+        "channels[].chain_1.port_id",
+        "channels[].chain_2",
+        "channels[].chain_2.channel_id",
+        "channels[].chain_2.port_id",
+        "channels[].ordering",
+        "channels[].version",
+    ];
+}
+
+/// Parses a registry file, reporting every unknown field, type mismatch and defaulted required field. Returns
+/// [`RegistryError::Parse`] if `data` isn't JSON or its root doesn't fit the model at all.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file, used in errors
+/// * `data` - The content of the file
+/// * `mode` - Whether diagnostics are errors or warnings
+pub fn parse<T>(path: &str, data: &str, mode: ParseMode) -> Result<Parsed<T>>
+where
+    T: DeserializeOwned + RequiredFields,
+{
+    let mut value: Value = serde_json::from_str(data).map_err(|e| RegistryError::parse(path, e))?;
+    let mut diagnostics = Vec::new();
+
+    for field in T::REQUIRED_FIELDS {
+        check_required(&value, field, &mut Vec::new(), &mut diagnostics);
+    }
+
+    // default each value that fails to deserialize until the rest of the file does. unknown keys are only removed
+    // from `checked` so that the models keep them in their extras.
+    let mut checked = value.clone();
+    while let Err(err) = serde_path_to_error::deserialize::<_, T>(Strict(&checked)) {
+        let at = error_path(&err);
+        let message = err.inner().to_string();
+
+        if message == UNKNOWN_FIELD_ERROR && remove(&mut checked, &at) {
+            push_unique(
+                &mut diagnostics,
+                Diagnostic::at(&at, DiagnosticKind::UnknownField),
+            );
+            continue;
+        }
+        // a mismatched field is removed so that the model defaults it, and a mismatched array element is replaced
+        // with an empty value that the model accepts, so that the indices of the elements after it don't change
+        let defaulted = match at.last() {
+            Some(Segment::Key(_)) => remove(&mut checked, &at) && remove(&mut value, &at),
+            Some(Segment::Index(_)) => match empty_element::<T>(&mut checked, &at) {
+                Some(empty) => replace(&mut value, &at, empty),
+                None => false,
+            },
+            None => false,
+        };
+        if !defaulted {
+            return Err(RegistryError::parse(path, err.into_inner()));
+        }
+        push_unique(
+            &mut diagnostics,
+            Diagnostic::at(&at, DiagnosticKind::TypeMismatch(message)),
+        );
+    }
+
+    let parsed: T = serde_json::from_value(value).map_err(|e| RegistryError::parse(path, e))?;
+
+    if mode == ParseMode::Strict && !diagnostics.is_empty() {
+        return Err(RegistryError::Schema {
+            path: path.to_string(),
+            diagnostics,
+        });
+    }
+
+    Ok(Parsed {
+        value: parsed,
+        diagnostics,
+    })
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+fn format_path(path: &[Segment]) -> String {
+    let mut out = String::new();
+    for segment in path {
+        match segment {
+            Segment::Key(key) => {
+                if !out.is_empty() {
+                    out.push('.');
+                }
+                out.push_str(key);
+            }
+            Segment::Index(index) => out.push_str(&format!("[{}]", index)),
+        }
+    }
+    out
+}
+
+fn error_path(err: &serde_path_to_error::Error<serde_json::Error>) -> Vec<Segment> {
+    let mut at = Vec::new();
+    for segment in err.path().iter() {
+        match segment {
+            serde_path_to_error::Segment::Map { key } => at.push(Segment::Key(key.clone())),
+            serde_path_to_error::Segment::Seq { index } => at.push(Segment::Index(*index)),
+            _ => break,
+        }
+    }
+    at
+}
+
+fn push_unique(diagnostics: &mut Vec<Diagnostic>, diagnostic: Diagnostic) {
+    if !diagnostics.contains(&diagnostic) {
+        diagnostics.push(diagnostic);
+    }
+}
+
+fn check_required(value: &Value, field: &str, at: &mut Vec<Segment>, out: &mut Vec<Diagnostic>) {
+    let (head, rest) = match field.split_once('.') {
+        Some((head, rest)) => (head, Some(rest)),
+        None => (field, None),
+    };
+    let (key, each) = match head.strip_suffix("[]") {
+        Some(key) => (key, true),
+        None => (head, false),
+    };
+    // a parent with the wrong type is reported as a type mismatch instead
+    let child = match value.as_object() {
+        Some(obj) => obj.get(key),
+        None => return,
+    };
+    at.push(Segment::Key(key.to_string()));
+
+    match (child, rest) {
+        (None, None) if !each => push_unique(
+            out,
+            Diagnostic {
+                path: format_path(at),
+                kind: DiagnosticKind::MissingField,
+            },
+        ),
+        (Some(Value::Array(items)), Some(rest)) if each => {
+            for (i, item) in items.iter().enumerate() {
+                at.push(Segment::Index(i));
+                check_required(item, rest, at, out);
+                at.pop();
+            }
+        }
+        (Some(child), Some(rest)) if !each => check_required(child, rest, at, out),
+        _ => {}
+    }
+
+    at.pop();
+}
+
+/// Replaces the array element at `at` with the first empty value that `T` accepts there, e.g. `{}` for a model or
+/// `""` for a string, and returns it
+fn empty_element<T: DeserializeOwned>(checked: &mut Value, at: &[Segment]) -> Option<Value> {
+    let empties = [json!({}), json!(""), json!(0), json!(false), json!([])];
+    for empty in empties {
+        if !replace(checked, at, empty.clone()) {
+            return None;
+        }
+        match serde_path_to_error::deserialize::<_, T>(Strict(checked)) {
+            Err(err) if error_path(&err) == at => continue,
+            _ => return Some(empty),
+        }
+    }
+    None
+}
+
+fn get_mut<'v>(value: &'v mut Value, at: &[Segment]) -> Option<&'v mut Value> {
+    at.iter().try_fold(value, |value, segment| match segment {
+        Segment::Key(key) => value.get_mut(key.as_str()),
+        Segment::Index(index) => value.get_mut(*index),
+    })
+}
+
+/// Replaces the value at `at`. Returns `false` if there's nothing there.
+fn replace(value: &mut Value, at: &[Segment], with: Value) -> bool {
+    match get_mut(value, at) {
+        Some(slot) => {
+            *slot = with;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Removes the field at `at` from its object. Returns `false` if there's nothing there.
+fn remove(value: &mut Value, at: &[Segment]) -> bool {
+    match at.split_last() {
+        Some((Segment::Key(key), parents)) => match get_mut(value, parents) {
+            Some(Value::Object(obj)) => obj.remove(key).is_some(),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::Explorer;
+    use assay::assay;
+
+    fn paths(parsed: &[Diagnostic]) -> Vec<&str> {
+        parsed.iter().map(|d| d.path.as_str()).collect()
+    }
+
+    #[assay]
+    fn clean_file_has_no_diagnostics() {
+        let data = r#"{
+            "chain_1": {"chain_name": "cosmoshub", "client_id": "07-tendermint-259", "connection_id": "connection-257"},
+            "chain_2": {"chain_name": "osmosis", "client_id": "07-tendermint-1", "connection_id": "connection-1"},
+            "channels": [{
+                "chain_1": {"channel_id": "channel-141", "port_id": "transfer"},
+                "chain_2": {"channel_id": "channel-0", "port_id": "transfer"},
+                "ordering": "unordered",
+                "version": "ics20-1"
+            }]
+        }"#;
+        let parsed =
+            parse::<IBCPath>("_IBC/cosmoshub-osmosis.json", data, ParseMode::Strict).unwrap();
+
+        assert!(parsed.diagnostics.is_empty());
+        assert_eq!(parsed.value.channels[0].chain_1.channel_id, "channel-141");
+    }
+
+    #[assay]
+    fn reports_unknown_fields() {
+        let data = r#"{
+            "chain_name": "osmosis",
+            "chain_id": "osmosis-1",
+            "bech32_prefix": "osmo",
+            "slip44": 118,
+            "apis": {"rpc": [
+                {"address": "a"}, {"address": "b"}, {"address": "c"},
+                {"address": "d", "provider": "p", "pruning": "none"}
+            ]},
+            "mintscan_name": {}
+        }"#;
+        let parsed = parse::<ChainInfo>("osmosis/chain.json", data, ParseMode::Lenient).unwrap();
+
+        assert_eq!(
+            paths(&parsed.diagnostics),
+            vec!["apis.rpc[3].pruning", "mintscan_name"]
+        );
+        assert!(parsed
+            .diagnostics
+            .iter()
+            .all(|d| d.kind == DiagnosticKind::UnknownField));
+        assert_eq!(parsed.value.apis.rpc.len(), 4);
+        assert_eq!(parsed.value.apis.rpc[3].extras["pruning"], "none");
+        assert!(parsed.value.extras.contains_key("mintscan_name"));
+    }
+
+    #[assay]
+    fn reports_every_type_mismatch() {
+        let data = r#"{
+            "chain_name": "osmosis",
+            "chain_id": "osmosis-1",
+            "bech32_prefix": "osmo",
+            "slip44": "118",
+            "key_algos": ["secp256k1", 7, "ethsecp256k1", false],
+            "explorers": ["mintscan", {"kind": "ping.pub"}],
+            "apis": {"rpc": [{"address": 1}, {"address": "b", "provider": 2}]}
+        }"#;
+        let parsed = parse::<ChainInfo>("osmosis/chain.json", data, ParseMode::Lenient).unwrap();
+
+        assert_eq!(
+            paths(&parsed.diagnostics),
+            vec![
+                "apis.rpc[0].address",
+                "apis.rpc[1].provider",
+                "explorers[0]",
+                "key_algos[1]",
+                "key_algos[3]",
+                "slip44"
+            ]
+        );
+        assert!(parsed
+            .diagnostics
+            .iter()
+            .all(|d| matches!(d.kind, DiagnosticKind::TypeMismatch(_))));
+        assert_eq!(parsed.value.slip44, 0);
+        // mismatched array elements are defaulted in place rather than removed
+        assert_eq!(
+            parsed.value.key_algos,
+            vec!["secp256k1", "", "ethsecp256k1", ""]
+        );
+        assert_eq!(parsed.value.apis.rpc[1].provider, None);
+        assert_eq!(parsed.value.explorers[0], Explorer::default());
+        assert_eq!(parsed.value.explorers[1].kind, "ping.pub");
+    }
+
+    #[assay]
+    fn reports_missing_required_fields() {
+        let data = r#"{
+            "chain_name": "osmosis",
+            "chain_id": "osmosis-1",
+            "slip44": 118,
+            "peers": {"persistent_peers": [{"id": "x", "address": "y"}, {"address": "z"}]}
+        }"#;
+        let parsed = parse::<ChainInfo>("osmosis/chain.json", data, ParseMode::Lenient).unwrap();
+
+        assert_eq!(
+            paths(&parsed.diagnostics),
+            vec!["bech32_prefix", "peers.persistent_peers[1].id"]
+        );
+        assert!(parsed
+            .diagnostics
+            .iter()
+            .all(|d| d.kind == DiagnosticKind::MissingField));
+        let peers = &parsed.value.peers.persistent_peers;
+        assert_eq!(peers.len(), 2);
+        assert_eq!(peers[1].id, "");
+        assert_eq!(peers[1].address, "z");
+    }
+
+    #[assay]
+    fn strict_mode_fails() {
+        let data = r#"{
+            "$schema": "../assetlist.schema.json",
+            "chain_name": "osmosis",
+            "assets": [],
+            "mintscan_name": "osmosis"
+        }"#;
+        let err =
+            parse::<AssetList>("osmosis/assetlist.json", data, ParseMode::Strict).unwrap_err();
+
+        match err {
+            RegistryError::Schema { path, diagnostics } => {
+                assert_eq!(path, "osmosis/assetlist.json");
+                assert_eq!(diagnostics.len(), 1);
+                assert_eq!(diagnostics[0].to_string(), "mintscan_name: unknown field");
+            }
+            _ => panic!("unexpected error {:?}", err),
+        }
+    }
+
+    #[assay]
+    fn strict_mode_accepts_registry_files() {
+        let data = include_str!("../tests/fixtures/registry/osmosis/assetlist.json");
+        let parsed = parse::<AssetList>("osmosis/assetlist.json", data, ParseMode::Strict).unwrap();
+
+        assert_eq!(parsed.value.schema, "../assetlist.schema.json");
+        assert!(parsed.value.extras.is_empty());
+    }
+
+    #[assay]
+    fn invalid_json_errors() {
+        let err =
+            parse::<AssetList>("osmosis/assetlist.json", "{", ParseMode::Lenient).unwrap_err();
+        assert!(matches!(err, RegistryError::Parse { .. }));
+
+        let err =
+            parse::<AssetList>("osmosis/assetlist.json", "42", ParseMode::Lenient).unwrap_err();
+        assert!(matches!(err, RegistryError::Parse { .. }));
+    }
+}
@@ -0,0 +1,285 @@
+//! A persistent on-disk cache for registry responses. Content at a given commit never changes, so responses are stored
+//! under `<dir>/<commit SHA>/<key>` and served from disk by later processes instead of being downloaded again.
+use crate::error::{RegistryError, Result};
+use std::{
+    path::{Component, Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+use tokio::fs;
+
+/// A directory of cached registry responses, optionally bounded in size. Clones share the same size accounting.
+///
+/// # Examples
+///
+/// ```ignore
+/// use chain_registry::{disk_cache::DiskCache, RegistryClient};
+///
+/// let cache = DiskCache::new("/var/cache/chain-registry").with_max_bytes(256 * 1024 * 1024);
+/// let client = RegistryClient::new().with_disk_cache(cache);
+/// ```
+#[derive(Clone, Debug)]
+pub struct DiskCache {
+    dir: PathBuf,
+    max_bytes: Option<u64>,
+    // the total size of the cached files, computed on the first write
+    size: Arc<Mutex<Option<u64>>>,
+}
+
+impl DiskCache {
+    /// Creates a cache in `dir`. The directory is created on the first write.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        DiskCache {
+            dir: dir.into(),
+            max_bytes: None,
+            size: Arc::default(),
+        }
+    }
+
+    /// Limits the total size of the cached files. When a write takes the cache over the limit, the least recently
+    /// written files are removed until it fits again.
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// The directory responses are stored in
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The size limit of the cache, if any
+    pub fn max_bytes(&self) -> Option<u64> {
+        self.max_bytes
+    }
+
+    /// Reads a cached response. Returns `None` if it isn't cached or can't be read.
+    ///
+    /// # Arguments
+    ///
+    /// * `commit` - The SHA of the commit the response was read at
+    /// * `key` - A relative path identifying the response, e.g. `files/osmosis/chain.json`
+    pub async fn get(&self, commit: &str, key: &str) -> Option<Vec<u8>> {
+        fs::read(self.entry_path(commit, key)?).await.ok()
+    }
+
+    /// Stores a response, replacing any previously cached one
+    ///
+    /// # Arguments
+    ///
+    /// * `commit` - The SHA of the commit the response was read at
+    /// * `key` - A relative path identifying the response, e.g. `files/osmosis/chain.json`
+    /// * `data` - The response body
+    pub async fn put(&self, commit: &str, key: &str, data: &[u8]) -> Result<()> {
+        let path = self.entry_path(commit, key).ok_or_else(|| {
+            RegistryError::Config(format!("invalid disk cache key {}/{}", commit, key))
+        })?;
+        let display = path.display().to_string();
+        let parent = path.parent().unwrap_or(&self.dir);
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| RegistryError::io(parent.display().to_string(), e))?;
+
+        // write to a temporary file first so that concurrent readers never see a partial response
+        let tmp = path.with_extension(format!("tmp-{}", rand::random::<u32>()));
+        let previous = fs::metadata(&path).await.map(|m| m.len()).unwrap_or(0);
+        fs::write(&tmp, data)
+            .await
+            .map_err(|e| RegistryError::io(&display, e))?;
+        fs::rename(&tmp, &path)
+            .await
+            .map_err(|e| RegistryError::io(&display, e))?;
+
+        if let Some(max_bytes) = self.max_bytes {
+            let size = match self.cached_size() {
+                Some(size) => size.saturating_sub(previous) + data.len() as u64,
+                None => self.size().await?,
+            };
+            self.set_size(size);
+            if size > max_bytes {
+                self.evict(max_bytes).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The total size in bytes of the cached responses
+    pub async fn size(&self) -> Result<u64> {
+        Ok(self.entries().await?.iter().map(|(_, _, len)| len).sum())
+    }
+
+    /// Removes every cached response
+    pub async fn clear(&self) -> Result<()> {
+        match fs::remove_dir_all(&self.dir).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(RegistryError::io(self.dir.display().to_string(), e)),
+        }
+        self.set_size(0);
+
+        Ok(())
+    }
+
+    /// Removes the least recently written files until the cache is no larger than `max_bytes`
+    async fn evict(&self, max_bytes: u64) -> Result<()> {
+        let mut entries = self.entries().await?;
+        let mut size: u64 = entries.iter().map(|(_, _, len)| len).sum();
+        entries.sort_by_key(|(_, modified, _)| *modified);
+
+        for (path, _, len) in entries {
+            if size <= max_bytes {
+                break;
+            }
+            match fs::remove_file(&path).await {
+                Ok(()) => size -= len,
+                // removed by another process or a concurrent eviction
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => size -= len,
+                Err(e) => return Err(RegistryError::io(path.display().to_string(), e)),
+            }
+        }
+        self.set_size(size);
+
+        Ok(())
+    }
+
+    /// Lists every cached file with its modification time and size
+    async fn entries(&self) -> Result<Vec<(PathBuf, SystemTime, u64)>> {
+        let mut entries = Vec::new();
+        let mut dirs = vec![self.dir.clone()];
+
+        while let Some(dir) = dirs.pop() {
+            let mut read_dir = match fs::read_dir(&dir).await {
+                Ok(read_dir) => read_dir,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(RegistryError::io(dir.display().to_string(), e)),
+            };
+            let io_err = |e| RegistryError::io(dir.display().to_string(), e);
+
+            while let Some(entry) = read_dir.next_entry().await.map_err(io_err)? {
+                let metadata = entry.metadata().await.map_err(io_err)?;
+                if metadata.is_dir() {
+                    dirs.push(entry.path());
+                } else {
+                    let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                    entries.push((entry.path(), modified, metadata.len()));
+                }
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// The location of a cached response, or `None` if the commit or key would escape the cache directory
+    fn entry_path(&self, commit: &str, key: &str) -> Option<PathBuf> {
+        let key = Path::new(key);
+        let safe = !commit.is_empty()
+            && commit.chars().all(|c| c.is_ascii_alphanumeric())
+            && key.components().next().is_some()
+            && key.components().all(|c| matches!(c, Component::Normal(_)));
+
+        safe.then(|| self.dir.join(commit).join(key))
+    }
+
+    fn cached_size(&self) -> Option<u64> {
+        *self.size.lock().expect("disk cache lock poisoned")
+    }
+
+    fn set_size(&self, size: u64) {
+        *self.size.lock().expect("disk cache lock poisoned") = Some(size);
+    }
+}
+
+/// Whether `git_ref` is a full commit SHA, whose content can be cached indefinitely
+pub(crate) fn is_commit_sha(git_ref: &str) -> bool {
+    git_ref.len() == 40 && git_ref.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assay::assay;
+
+    const COMMIT: &str = "350840e766f7574a120760a13eda4c466413308a";
+
+    #[assay]
+    async fn stores_and_reads_responses() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::new(dir.path());
+
+        assert_eq!(cache.get(COMMIT, "files/osmosis/chain.json").await, None);
+        cache
+            .put(COMMIT, "files/osmosis/chain.json", b"{}")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            cache.get(COMMIT, "files/osmosis/chain.json").await,
+            Some(b"{}".to_vec())
+        );
+        assert_eq!(cache.get("0000", "files/osmosis/chain.json").await, None);
+        assert!(dir
+            .path()
+            .join(COMMIT)
+            .join("files/osmosis/chain.json")
+            .exists());
+    }
+
+    #[assay]
+    async fn evicts_oldest_files_over_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::new(dir.path()).with_max_bytes(25);
+
+        // age the files explicitly rather than relying on the filesystem's mtime granularity
+        let age = |key: &str, secs: u64| {
+            let mtime = SystemTime::now() - std::time::Duration::from_secs(secs);
+            filetime::set_file_mtime(
+                dir.path().join(COMMIT).join(key),
+                filetime::FileTime::from_system_time(mtime),
+            )
+            .unwrap();
+        };
+        cache.put(COMMIT, "a", &[0; 10]).await.unwrap();
+        age("a", 120);
+        cache.put(COMMIT, "b", &[0; 10]).await.unwrap();
+        age("b", 60);
+        cache.put(COMMIT, "c", &[0; 10]).await.unwrap();
+
+        assert_eq!(cache.get(COMMIT, "a").await, None);
+        assert!(cache.get(COMMIT, "b").await.is_some());
+        assert!(cache.get(COMMIT, "c").await.is_some());
+        assert_eq!(cache.size().await.unwrap(), 20);
+    }
+
+    #[assay]
+    async fn clears_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::new(dir.path().join("cache"));
+
+        cache.put(COMMIT, "tree.json", b"[]").await.unwrap();
+        cache.clear().await.unwrap();
+
+        assert_eq!(cache.get(COMMIT, "tree.json").await, None);
+        assert_eq!(cache.size().await.unwrap(), 0);
+        // clearing an empty cache is fine
+        cache.clear().await.unwrap();
+    }
+
+    #[assay]
+    async fn rejects_keys_outside_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiskCache::new(dir.path());
+
+        assert!(cache.put(COMMIT, "../escape", b"x").await.is_err());
+        assert!(cache.put("../x", "file", b"x").await.is_err());
+        assert!(cache.put(COMMIT, "/etc/passwd", b"x").await.is_err());
+        assert!(cache.put(COMMIT, "", b"x").await.is_err());
+    }
+
+    #[assay]
+    fn detects_commit_shas() {
+        assert!(is_commit_sha(COMMIT));
+        assert!(!is_commit_sha("master"));
+        assert!(!is_commit_sha(&COMMIT[..7]));
+    }
+}
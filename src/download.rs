@@ -0,0 +1,705 @@
+//! Downloads the chain binaries listed in a codebase's [`Binaries`](crate::chain::Binaries) and chain genesis files.
+//! Each download is streamed to disk, checked against the hash the registry declares for it and unpacked if it's an
+//! archive.
+use crate::{
+    chain::{Binary, ChainInfo, Checksum},
+    error::{RegistryError, Result},
+};
+use flate2::read::GzDecoder;
+use http::StatusCode;
+use serde::Deserialize;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
+use std::{
+    fs::File,
+    future::Future,
+    io::{self, BufRead, BufReader, Read, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tokio::{fs, io::AsyncWriteExt};
+
+/// Downloads `binary` into `dir` with `http` within `timeout`, returning the paths of the files written
+pub(crate) async fn download_binary(
+    http: &reqwest::Client,
+    timeout: Duration,
+    binary: &Binary,
+    dir: &Path,
+) -> Result<Vec<PathBuf>> {
+    let url = binary.url.as_str();
+    let mut hasher = binary.checksum.as_ref().map(Hasher::new).transpose()?;
+    let name = file_name(url).ok_or_else(|| RegistryError::InvalidResponse {
+        url: url.to_string(),
+        reason: "the URL doesn't end with a file name".to_string(),
+    })?;
+
+    // download next to the destination so that a failed or tampered download never replaces a good binary
+    let tmp = TempFile::in_dir(dir).await?;
+    download_to(http, timeout, url, &tmp.0, hasher.as_mut()).await?;
+
+    if let (Some(hasher), Some(expected)) = (hasher, &binary.checksum) {
+        let actual = hasher.finalize();
+        if actual != expected.digest {
+            return Err(RegistryError::Integrity {
+                path: url.to_string(),
+                expected: expected.digest.clone(),
+                actual,
+            });
+        }
+    }
+
+    install(&tmp.0, dir, name, url).await
+}
+
+/// Downloads the genesis file of `chain` to `dest` with `http` within `timeout`, decompressing it if it's gzipped and unpacking it if
+/// it's in a tarball. The file is checked against `genesis_sha256`, if declared, and its `chain_id` against the chain's.
+pub(crate) async fn download_genesis(
+    http: &reqwest::Client,
+    timeout: Duration,
+    chain: &ChainInfo,
+    dest: &Path,
+) -> Result<()> {
+    let url = chain.genesis.genesis_url.as_str();
+    if url.is_empty() {
+        return Err(RegistryError::NotFound {
+            path: format!("{}/chain.json genesis_url", chain.chain_name),
+        });
+    }
+
+    let dir = match dest.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let download = TempFile::in_dir(dir).await?;
+    let mut download_hasher = Hasher::Sha256(Sha256::new());
+    download_to(http, timeout, url, &download.0, Some(&mut download_hasher)).await?;
+
+    let genesis = TempFile::in_dir(dir).await?;
+    let genesis_sha256 = {
+        let (download, genesis, url) = (download.0.clone(), genesis.0.clone(), url.to_string());
+        run_blocking(move || extract_genesis(&download, &genesis, &url)).await?
+    };
+
+    // the declared hash may be of the file as published or of the decompressed genesis
+    if let Some(expected) = &chain.genesis.genesis_sha256 {
+        let expected = expected.to_ascii_lowercase();
+        if expected != genesis_sha256 && expected != download_hasher.finalize() {
+            return Err(RegistryError::Integrity {
+                path: url.to_string(),
+                expected,
+                actual: genesis_sha256,
+            });
+        }
+    }
+
+    let chain_id = {
+        let (genesis, url) = (genesis.0.clone(), url.to_string());
+        run_blocking(move || read_chain_id(&genesis, &url)).await?
+    };
+    if chain_id != chain.chain_id {
+        return Err(RegistryError::InvalidResponse {
+            url: url.to_string(),
+            reason: format!(
+                "genesis chain_id {} doesn't match chain.json chain_id {}",
+                chain_id, chain.chain_id
+            ),
+        });
+    }
+
+    fs::rename(&genesis.0, dest)
+        .await
+        .map_err(|e| RegistryError::io(dest.display().to_string(), e))
+}
+
+/// Streams the body of `url` into a new file at `path`, feeding it to `hasher` along the way. `timeout` bounds the
+/// whole download and overrides the HTTP client's own timeout.
+async fn download_to(
+    http: &reqwest::Client,
+    timeout: Duration,
+    url: &str,
+    path: &Path,
+    mut hasher: Option<&mut Hasher>,
+) -> Result<()> {
+    let mut response = http.get(url).timeout(timeout).send().await?;
+    if response.status() == StatusCode::NOT_FOUND {
+        return Err(RegistryError::NotFound {
+            path: url.to_string(),
+        });
+    }
+    if !response.status().is_success() {
+        return Err(RegistryError::Http {
+            url: url.to_string(),
+            status: response.status(),
+        });
+    }
+
+    let io_err = |e| RegistryError::io(path.display().to_string(), e);
+    let mut file = fs::File::create(path).await.map_err(io_err)?;
+
+    while let Some(chunk) = response.chunk().await? {
+        if let Some(hasher) = &mut hasher {
+            hasher.update(&chunk);
+        }
+        file.write_all(&chunk).await.map_err(io_err)?;
+    }
+
+    file.flush().await.map_err(io_err)
+}
+
+/// Writes the genesis in `download` to `dest`, decompressing gzip and unpacking a tarball as needed, and returns the
+/// hex-encoded SHA-256 of the result
+fn extract_genesis(download: &Path, dest: &Path, url: &str) -> Result<String> {
+    let io_err = |e| RegistryError::io(download.display().to_string(), e);
+    let mut reader: Box<dyn BufRead> =
+        Box::new(BufReader::new(File::open(download).map_err(io_err)?));
+
+    if reader
+        .fill_buf()
+        .map_err(io_err)?
+        .starts_with(&[0x1f, 0x8b])
+    {
+        reader = Box::new(BufReader::new(GzDecoder::new(reader)));
+    }
+
+    // a tar header carries the `ustar` magic at offset 257, which may span several reads of a gzip stream
+    let mut head = Vec::with_capacity(512);
+    (&mut reader)
+        .take(512)
+        .read_to_end(&mut head)
+        .map_err(io_err)?;
+    let reader = io::Cursor::new(head).chain(reader);
+    let mut hasher = Sha256::new();
+    let mut out = HashingWriter {
+        inner: File::create(dest).map_err(|e| RegistryError::io(dest.display().to_string(), e))?,
+        hasher: &mut hasher,
+    };
+    let copy_err = |e| RegistryError::io(dest.display().to_string(), e);
+
+    if is_tar(reader.get_ref().0.get_ref()) {
+        let mut archive = tar::Archive::new(reader);
+        let mut found = false;
+        for entry in archive.entries().map_err(io_err)? {
+            let mut entry = entry.map_err(io_err)?;
+            let is_json = entry
+                .path()
+                .map_err(io_err)?
+                .extension()
+                .is_some_and(|ext| ext == "json");
+            if entry.header().entry_type().is_file() && is_json {
+                io::copy(&mut entry, &mut out).map_err(copy_err)?;
+                found = true;
+                break;
+            }
+        }
+        if !found {
+            return Err(RegistryError::InvalidResponse {
+                url: url.to_string(),
+                reason: "the genesis tarball doesn't contain a JSON file".to_string(),
+            });
+        }
+    } else {
+        let mut reader = reader;
+        io::copy(&mut reader, &mut out).map_err(copy_err)?;
+    }
+    out.inner.flush().map_err(copy_err)?;
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
+fn is_tar(head: &[u8]) -> bool {
+    head.get(257..262) == Some(b"ustar")
+}
+
+/// Reads the `chain_id` of a genesis file without holding the rest of it in memory
+fn read_chain_id(path: &Path, url: &str) -> Result<String> {
+    #[derive(Deserialize)]
+    struct GenesisDoc {
+        chain_id: String,
+    }
+
+    let file = File::open(path).map_err(|e| RegistryError::io(path.display().to_string(), e))?;
+    let doc: GenesisDoc =
+        serde_json::from_reader(BufReader::new(file)).map_err(|e| RegistryError::parse(url, e))?;
+
+    Ok(doc.chain_id)
+}
+
+/// Passes writes through to a file while hashing them
+struct HashingWriter<'a> {
+    inner: File,
+    hasher: &'a mut Sha256,
+}
+
+impl Write for HashingWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// A download in progress, removed when dropped unless it has been moved into place
+struct TempFile(PathBuf);
+
+impl TempFile {
+    /// Creates `dir` if needed and reserves a temporary path in it
+    async fn in_dir(dir: &Path) -> Result<TempFile> {
+        fs::create_dir_all(dir)
+            .await
+            .map_err(|e| RegistryError::io(dir.display().to_string(), e))?;
+
+        Ok(TempFile(
+            dir.join(format!(".download-{}", rand::random::<u32>())),
+        ))
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        // already gone if it was renamed into place
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Moves a verified download into place, unpacking it first if it's an archive
+async fn install(tmp: &Path, dir: &Path, name: &str, url: &str) -> Result<Vec<PathBuf>> {
+    let lower = name.to_ascii_lowercase();
+
+    if lower.ends_with(".tar.gz") || lower.ends_with(".tgz") {
+        let (tmp, dir) = (tmp.to_path_buf(), dir.to_path_buf());
+        return run_blocking(move || unpack_tar_gz(&tmp, &dir)).await;
+    }
+    if lower.ends_with(".zip") {
+        let (tmp, dir, url) = (tmp.to_path_buf(), dir.to_path_buf(), url.to_string());
+        return run_blocking(move || unpack_zip(&tmp, &dir, &url)).await;
+    }
+
+    let path = dir.join(name);
+    let io_err = |e| RegistryError::io(path.display().to_string(), e);
+    fs::rename(tmp, &path).await.map_err(io_err)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+            .await
+            .map_err(io_err)?;
+    }
+
+    Ok(vec![path])
+}
+
+/// Runs synchronous file work on the blocking thread pool so it doesn't stall the async runtime. The work starts
+/// right away, before the returned future is polled.
+pub(crate) fn run_blocking<T, F>(f: F) -> impl Future<Output = Result<T>>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    let task = tokio::task::spawn_blocking(f);
+
+    async move {
+        match task.await {
+            Ok(result) => result,
+            Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+            Err(e) => Err(RegistryError::io(
+                "blocking task",
+                io::Error::new(io::ErrorKind::Interrupted, e),
+            )),
+        }
+    }
+}
+
+fn unpack_tar_gz(archive: &Path, dir: &Path) -> Result<Vec<PathBuf>> {
+    let io_err = |e| RegistryError::io(archive.display().to_string(), e);
+    let file = File::open(archive).map_err(io_err)?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+    let mut files = Vec::new();
+
+    for entry in archive.entries().map_err(io_err)? {
+        let mut entry = entry.map_err(io_err)?;
+        let path = entry.path().map_err(io_err)?.into_owned();
+        let is_file = entry.header().entry_type().is_file();
+
+        // entries that would land outside `dir` are skipped
+        if entry.unpack_in(dir).map_err(io_err)? && is_file {
+            files.push(dir.join(path));
+        }
+    }
+
+    Ok(files)
+}
+
+fn unpack_zip(archive: &Path, dir: &Path, url: &str) -> Result<Vec<PathBuf>> {
+    let file =
+        File::open(archive).map_err(|e| RegistryError::io(archive.display().to_string(), e))?;
+    let zip_err = |e: zip::result::ZipError| RegistryError::InvalidResponse {
+        url: url.to_string(),
+        reason: format!("invalid zip archive: {}", e),
+    };
+    let mut archive = zip::ZipArchive::new(file).map_err(zip_err)?;
+    let mut files = Vec::new();
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(zip_err)?;
+        // entries that would land outside `dir` are skipped
+        let Some(name) = entry.enclosed_name().map(Path::to_path_buf) else {
+            continue;
+        };
+        let path = dir.join(name);
+        let io_err = |e| RegistryError::io(path.display().to_string(), e);
+
+        if entry.is_dir() {
+            std::fs::create_dir_all(&path).map_err(io_err)?;
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(io_err)?;
+        }
+        let mut out = File::create(&path).map_err(io_err)?;
+        io::copy(&mut entry, &mut out).map_err(io_err)?;
+        #[cfg(unix)]
+        if let Some(mode) = entry.unix_mode() {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode))
+                .map_err(io_err)?;
+        }
+        files.push(path);
+    }
+
+    Ok(files)
+}
+
+/// The last segment of a URL's path
+fn file_name(url: &str) -> Option<&str> {
+    let path = url.split(['?', '#']).next()?;
+    let (_, name) = path.rsplit_once('/')?;
+
+    (!name.is_empty()).then_some(name)
+}
+
+/// Hashes a download with the algorithm of the checksum it's checked against
+enum Hasher {
+    Sha1(Sha1),
+    Sha256(Sha256),
+    Sha512(Sha512),
+}
+
+impl Hasher {
+    fn new(checksum: &Checksum) -> Result<Hasher> {
+        match checksum.algorithm.as_str() {
+            "sha1" => Ok(Hasher::Sha1(Sha1::new())),
+            "sha256" => Ok(Hasher::Sha256(Sha256::new())),
+            "sha512" => Ok(Hasher::Sha512(Sha512::new())),
+            other => Err(RegistryError::Config(format!(
+                "unsupported checksum algorithm {}",
+                other
+            ))),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Sha1(h) => h.update(data),
+            Hasher::Sha256(h) => h.update(data),
+            Hasher::Sha512(h) => h.update(data),
+        }
+    }
+
+    /// The hex-encoded digest
+    fn finalize(self) -> String {
+        match self {
+            Hasher::Sha1(h) => hex::encode(h.finalize()),
+            Hasher::Sha256(h) => hex::encode(h.finalize()),
+            Hasher::Sha512(h) => hex::encode(h.finalize()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        client::HttpConfig,
+        test_server::{Response, TestServer},
+        RegistryClient,
+    };
+    use assay::assay;
+    use flate2::{write::GzEncoder, Compression};
+
+    const DAEMON: &[u8] = b"#!/bin/sh\necho osmosisd\n";
+
+    fn sha256(data: &[u8]) -> Checksum {
+        Checksum {
+            algorithm: "sha256".to_string(),
+            digest: hex::encode(Sha256::digest(data)),
+        }
+    }
+
+    fn binary(server: &TestServer, file: &str, checksum: Option<Checksum>) -> Binary {
+        Binary {
+            platform: "linux/amd64".to_string(),
+            url: format!("{}/releases/{}", server.url(), file),
+            checksum,
+        }
+    }
+
+    #[assay]
+    async fn downloads_and_verifies_binary() {
+        let server = TestServer::start(|_| Response::new(200, DAEMON));
+        let dir = tempfile::tempdir().unwrap();
+        let binary = binary(&server, "osmosisd-linux-amd64", Some(sha256(DAEMON)));
+
+        let files = RegistryClient::new()
+            .download_binary(&binary, dir.path())
+            .await
+            .unwrap();
+
+        assert_eq!(files, vec![dir.path().join("osmosisd-linux-amd64")]);
+        assert_eq!(std::fs::read(&files[0]).unwrap(), DAEMON);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&files[0]).unwrap().permissions().mode();
+            assert_eq!(mode & 0o111, 0o111);
+        }
+    }
+
+    #[assay]
+    async fn rejects_checksum_mismatch() {
+        let server = TestServer::start(|_| Response::new(200, "tampered"));
+        let dir = tempfile::tempdir().unwrap();
+        let binary = binary(&server, "osmosisd", Some(sha256(DAEMON)));
+
+        let err = RegistryClient::new()
+            .download_binary(&binary, dir.path())
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, RegistryError::Integrity { .. }));
+        // nothing is left behind
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[assay]
+    async fn unpacks_tar_gz() {
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::fast()));
+        let mut header = tar::Header::new_gnu();
+        header.set_size(DAEMON.len() as u64);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "bin/osmosisd", DAEMON)
+            .unwrap();
+        let archive = builder.into_inner().unwrap().finish().unwrap();
+        let checksum = sha256(&archive);
+        let server = TestServer::start(move |_| Response::new(200, archive.clone()));
+        let dir = tempfile::tempdir().unwrap();
+
+        let files = RegistryClient::new()
+            .download_binary(
+                &binary(&server, "osmosisd.tar.gz", Some(checksum)),
+                dir.path(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(files, vec![dir.path().join("bin/osmosisd")]);
+        assert_eq!(std::fs::read(&files[0]).unwrap(), DAEMON);
+        assert!(!dir.path().join("osmosisd.tar.gz").exists());
+    }
+
+    #[assay]
+    async fn unpacks_zip() {
+        let mut writer = zip::ZipWriter::new(io::Cursor::new(Vec::new()));
+        writer
+            .start_file("osmosisd.exe", zip::write::FileOptions::default())
+            .unwrap();
+        writer.write_all(DAEMON).unwrap();
+        let archive = writer.finish().unwrap().into_inner();
+        let server = TestServer::start(move |_| Response::new(200, archive.clone()));
+        let dir = tempfile::tempdir().unwrap();
+
+        // downloads without a declared checksum aren't verified
+        let files = RegistryClient::new()
+            .download_binary(&binary(&server, "osmosisd.zip", None), dir.path())
+            .await
+            .unwrap();
+
+        assert_eq!(files, vec![dir.path().join("osmosisd.exe")]);
+        assert_eq!(std::fs::read(&files[0]).unwrap(), DAEMON);
+    }
+
+    #[assay]
+    async fn unsupported_checksum_errors() {
+        let server = TestServer::start(|_| Response::new(200, DAEMON));
+        let checksum = Checksum {
+            algorithm: "md5".to_string(),
+            digest: "abc".to_string(),
+        };
+        let dir = tempfile::tempdir().unwrap();
+
+        let err = RegistryClient::new()
+            .download_binary(&binary(&server, "osmosisd", Some(checksum)), dir.path())
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, RegistryError::Config(_)));
+        assert!(server.requests().is_empty());
+    }
+
+    const GENESIS: &[u8] = br#"{"chain_id":"osmosis-1","app_state":{}}"#;
+
+    fn chain(server: &TestServer, file: &str, genesis_sha256: Option<&[u8]>) -> ChainInfo {
+        let mut chain = ChainInfo {
+            chain_name: "osmosis".to_string(),
+            chain_id: "osmosis-1".to_string(),
+            ..ChainInfo::default()
+        };
+        chain.genesis.genesis_url = format!("{}/{}", server.url(), file);
+        chain.genesis.genesis_sha256 = genesis_sha256.map(|data| sha256(data).digest);
+
+        chain
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+        encoder.write_all(data).unwrap();
+
+        encoder.finish().unwrap()
+    }
+
+    #[assay]
+    async fn downloads_and_verifies_genesis() {
+        let server = TestServer::start(|_| Response::new(200, GENESIS));
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("config/genesis.json");
+
+        RegistryClient::new()
+            .download_genesis(&chain(&server, "genesis.json", Some(GENESIS)), &dest)
+            .await
+            .unwrap();
+
+        assert_eq!(std::fs::read(&dest).unwrap(), GENESIS);
+        assert_eq!(
+            std::fs::read_dir(dest.parent().unwrap()).unwrap().count(),
+            1
+        );
+    }
+
+    #[assay]
+    async fn decompresses_gzipped_genesis() {
+        let server = TestServer::start(|_| Response::new(200, gzip(GENESIS)));
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("genesis.json");
+
+        // the declared hash is of the decompressed genesis
+        RegistryClient::new()
+            .download_genesis(&chain(&server, "genesis.json.gz", Some(GENESIS)), &dest)
+            .await
+            .unwrap();
+
+        assert_eq!(std::fs::read(&dest).unwrap(), GENESIS);
+    }
+
+    #[assay]
+    async fn unpacks_genesis_tarball() {
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(GENESIS.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "osmosis-1/genesis.json", GENESIS)
+            .unwrap();
+        let archive = gzip(&builder.into_inner().unwrap());
+        let server = TestServer::start({
+            let archive = archive.clone();
+            move |_| Response::new(200, archive.clone())
+        });
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("genesis.json");
+
+        // the declared hash is of the tarball as published
+        RegistryClient::new()
+            .download_genesis(&chain(&server, "genesis.tar.gz", Some(&archive)), &dest)
+            .await
+            .unwrap();
+
+        assert_eq!(std::fs::read(&dest).unwrap(), GENESIS);
+    }
+
+    #[assay]
+    async fn rejects_genesis_hash_mismatch() {
+        let server = TestServer::start(|_| Response::new(200, GENESIS));
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("genesis.json");
+
+        let err = RegistryClient::new()
+            .download_genesis(&chain(&server, "genesis.json", Some(b"{}")), &dest)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, RegistryError::Integrity { .. }));
+        // nothing is left behind
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[assay]
+    async fn rejects_genesis_for_another_chain() {
+        let server = TestServer::start(|_| Response::new(200, r#"{"chain_id":"osmo-test-5"}"#));
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("genesis.json");
+
+        // genesis files without a declared hash are only checked against the chain ID
+        let err = RegistryClient::new()
+            .download_genesis(&chain(&server, "genesis.json", None), &dest)
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(err, RegistryError::InvalidResponse { ref reason, .. } if reason.contains("osmo-test-5"))
+        );
+        assert!(!dest.exists());
+    }
+
+    #[assay]
+    async fn slow_download_outlasts_request_timeout() {
+        let server = TestServer::start(|_| {
+            Response::new(200, DAEMON).with_body_delay(Duration::from_millis(500))
+        });
+        let dir = tempfile::tempdir().unwrap();
+        let client = RegistryClient::new()
+            .with_http_config(HttpConfig {
+                request_timeout: Some(Duration::from_millis(100)),
+                ..HttpConfig::default()
+            })
+            .unwrap();
+
+        // downloads are bounded by the download timeout rather than the request timeout
+        let files = client
+            .download_binary(&binary(&server, "osmosisd", None), dir.path())
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&files[0]).unwrap(), DAEMON);
+
+        let err = client
+            .with_download_timeout(Duration::from_millis(100))
+            .download_binary(&binary(&server, "osmosisd", None), dir.path())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RegistryError::Request(ref e) if e.is_timeout()));
+    }
+
+    #[assay]
+    fn finds_file_names() {
+        assert_eq!(
+            file_name("https://example.com/v1/osmosisd?x=1"),
+            Some("osmosisd")
+        );
+        assert_eq!(file_name("https://example.com/"), None);
+    }
+}
@@ -0,0 +1,108 @@
+//! The error type returned by every fallible operation in this crate
+use crate::diagnostics::Diagnostic;
+use http::StatusCode;
+use std::time::SystemTime;
+
+/// A `Result` alias with [`RegistryError`] as the default error type
+pub type Result<T, E = RegistryError> = std::result::Result<T, E>;
+
+/// Errors that can occur while retrieving or parsing registry data
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum RegistryError {
+    /// The requested file or directory doesn't exist in the registry
+    #[error("{path} not found")]
+    NotFound { path: String },
+
+    /// The server responded with an unexpected status
+    #[error("request to {url} failed with status {status}")]
+    Http { url: String, status: StatusCode },
+
+    /// GitHub rejected the request because the caller's rate limit is exhausted
+    #[error("GitHub rate limit exceeded, resets in {} seconds", seconds_until(.reset))]
+    RateLimited {
+        /// When requests will be accepted again
+        reset: SystemTime,
+    },
+
+    /// A file was retrieved but its content doesn't match the expected schema
+    #[error("error parsing {path}: {source}")]
+    Parse {
+        path: String,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// A file was parsed in [`ParseMode::Strict`](crate::diagnostics::ParseMode::Strict) and doesn't match its model
+    #[error("{path} doesn't match the registry schema: {}", join(.diagnostics))]
+    Schema {
+        path: String,
+        diagnostics: Vec<Diagnostic>,
+    },
+
+    /// A local file, directory or archive couldn't be read
+    #[error("error reading {path}: {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// The request couldn't be sent or its response couldn't be read
+    #[error("request failed: {0}")]
+    Request(#[from] reqwest::Error),
+
+    /// A downloaded file doesn't hash to the blob SHA listed for it in the registry's git tree, or to the checksum the
+    /// registry declares for it, so it was altered in transit or at its source
+    #[error("integrity check failed for {path}: expected {expected}, got {actual}")]
+    Integrity {
+        path: String,
+        /// The blob SHA from the git tree or the declared checksum
+        expected: String,
+        /// The hash of the content that was received
+        actual: String,
+    },
+
+    /// A server returned a response that couldn't be used
+    #[error("invalid response from {url}: {reason}")]
+    InvalidResponse { url: String, reason: String },
+
+    /// The client was configured with an invalid value
+    #[error("invalid configuration: {0}")]
+    Config(String),
+}
+
+impl RegistryError {
+    /// Whether this error means the requested file or directory doesn't exist
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, RegistryError::NotFound { .. })
+    }
+
+    pub(crate) fn io(path: impl Into<String>, source: std::io::Error) -> Self {
+        RegistryError::Io {
+            path: path.into(),
+            source,
+        }
+    }
+
+    pub(crate) fn parse(path: impl Into<String>, source: serde_json::Error) -> Self {
+        RegistryError::Parse {
+            path: path.into(),
+            source,
+        }
+    }
+}
+
+fn join(diagnostics: &[Diagnostic]) -> String {
+    diagnostics
+        .iter()
+        .map(|d| d.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn seconds_until(time: &SystemTime) -> u64 {
+    time.duration_since(SystemTime::now())
+        .unwrap_or_default()
+        .as_secs()
+}
@@ -1,141 +1,159 @@
-use crate::github::Content;
-use eyre::{eyre, Context, Result};
-use http::{Method, StatusCode};
-use serde::de::DeserializeOwned;
+use crate::{
+    client::RegistryClient,
+    error::Result,
+    source::{
+        assets_file, chain_file, load_unordered, read_json, RegistrySource, DEFAULT_CONCURRENCY,
+    },
+};
+use futures::stream::BoxStream;
+use std::sync::Arc;
 
 pub use crate::{assets::*, chain::*, paths::*};
 
-const VERSION: &str = env!("CARGO_PKG_VERSION");
-// In the future we may want to provide a way for a user to set the desired ref for the registry
-// module to use when querying.
-const GIT_REF: &str = "350840e766f7574a120760a13eda4c466413308a";
-const RAW_FILE_REPO_URL: &str = "https://raw.githubusercontent.com/cosmos/chain-registry";
-const REPO_URL: &str = "https://api.github.com/repos/cosmos/chain-registry/contents";
-
-async fn get(url: String) -> Result<String> {
-    let client = reqwest::Client::new();
-    let req = client
-        .request(Method::GET, url)
-        .header("User-Agent", format!("ocular/{}", VERSION))
-        .build()?;
-    Ok(client.execute(req).await?.text().await?)
-}
-
 /// Gets a list of chain names from the registry
 pub async fn list_chains() -> Result<Vec<String>> {
-    let url = format!("{}?ref={}", REPO_URL, GIT_REF,);
-    let json: String = get(url).await?;
-    let contents: Vec<Content> = serde_json::from_str(json.as_str())?;
-
-    Ok(contents
-        .iter()
-        .filter(|c| c.type_field == "dir" && !c.name.starts_with('_') && c.name != ".github")
-        .map(|c| c.clone().name)
-        .collect())
+    RegistryClient::default().list_chains().await
 }
 
 /// Gets a list of path names from the registry in the form <chain_a>-<chain_b>
 pub async fn list_paths() -> Result<Vec<String>> {
-    let url = format!("{}/_IBC?ref={}", REPO_URL, GIT_REF,);
-    let json: String = get(url).await?;
-    let contents: Vec<Content> = serde_json::from_str(json.as_str())?;
-
-    Ok(contents
-        .iter()
-        .filter(|c| c.type_field == "file" && !c.name.starts_with('_') && c.name.ends_with(".json"))
-        .map(|c| c.name[..c.name.len() - ".json".len()].to_string())
-        .collect())
+    RegistryClient::default().list_paths().await
 }
 
-/// Retrieves the deserialized `assets.json` for a given chain. The result will contain
-/// `None` if the there is no `assets.json` present.
+/// Retrieves the deserialized `assets.json` for a given chain. Returns [`RegistryError::NotFound`](crate::RegistryError::NotFound)
+/// if there is no `assets.json` present and [`RegistryError::Parse`](crate::RegistryError::Parse) if it can't be parsed.
 ///
 /// # Arguments
 ///
 /// * `name` - The chain name. Must match the name of the chain's folder in the root directory of the
-/// [chain registry](https://github.com/cosmos/chain-registry).
+///   [chain registry](https://github.com/cosmos/chain-registry).
 pub async fn get_assets(name: &str) -> Result<Option<AssetList>> {
-    let path = format!("{}/assetlist.json", name);
-    let data = get_file_content(GIT_REF, &path).await?;
-
-    Ok(parse_json(data).await)
+    RegistryClient::default().get_assets(name).await
 }
 
-/// Retrieves the deserialized `chain.json` for a given chain. The result will contain
-/// `None` if the there is no `chain.json` present.
+/// Retrieves the deserialized `chain.json` for a given chain. Returns [`RegistryError::NotFound`](crate::RegistryError::NotFound)
+/// if there is no `chain.json` present and [`RegistryError::Parse`](crate::RegistryError::Parse) if it can't be parsed.
 ///
 /// # Arguments
 ///
 /// * `name` - The chain name. Must match the name of the chain's folder in the root directory of the
-/// [chain registry](https://github.com/cosmos/chain-registry).
+///   [chain registry](https://github.com/cosmos/chain-registry).
 pub async fn get_chain(name: &str) -> Result<Option<ChainInfo>> {
-    let path = format!("{}/chain.json", name);
-    let data = get_file_content(GIT_REF, &path).await?;
-
-    Ok(parse_json(data).await)
+    RegistryClient::default().get_chain(name).await
 }
 
-/// Retrieves the deserialized IBC path json for a given pair of chains. The result will contain
-/// `None` if the there is no path present.
+/// Retrieves the deserialized IBC path json for a given pair of chains. Returns [`RegistryError::NotFound`](crate::RegistryError::NotFound)
+/// if there is no path present and [`RegistryError::Parse`](crate::RegistryError::Parse) if it can't be parsed.
 ///
 /// # Arguments
 ///
 /// * `name` - The chain name. Must match the name of the chain's folder in the root directory of the
-/// [chain registry](https://github.com/cosmos/chain-registry).
+///   [chain registry](https://github.com/cosmos/chain-registry).
 pub async fn get_path(chain_a: &str, chain_b: &str) -> Result<Option<IBCPath>> {
-    // path names order the chain names alphabetically
-    let path = format!(
-        "_IBC/{}-{}.json",
-        chain_a.min(chain_b),
-        chain_a.max(chain_b)
-    );
-    let data = get_file_content(GIT_REF, &path).await?;
-
-    Ok(parse_json(data).await)
+    RegistryClient::default().get_path(chain_a, chain_b).await
 }
 
-async fn get_file_content(r#ref: &str, path: &str) -> Result<String> {
-    let url = format!("{}/{}/{}", RAW_FILE_REPO_URL, r#ref, path);
-    let response = reqwest::get(url).await?; //.text().await?
+/// Gets a list of testnet chain names from the `testnets` directory of the registry
+pub async fn list_testnets() -> Result<Vec<String>> {
+    RegistryClient::default().list_testnets().await
+}
 
-    if response.status() == StatusCode::NOT_FOUND {
-        return Err(eyre!("path {} not found", path));
-    }
+/// Retrieves the deserialized `assetlist.json` for a chain in the `testnets` directory. See [`get_assets`].
+///
+/// # Arguments
+///
+/// * `name` - The chain name. Must match the name of the chain's folder in the `testnets` directory of the
+///   [chain registry](https://github.com/cosmos/chain-registry).
+pub async fn get_testnet_assets(name: &str) -> Result<Option<AssetList>> {
+    RegistryClient::default().get_testnet_assets(name).await
+}
 
-    response
-        .text()
+/// Retrieves the deserialized `chain.json` for a chain in the `testnets` directory. See [`get_chain`].
+///
+/// # Arguments
+///
+/// * `name` - The chain name. Must match the name of the chain's folder in the `testnets` directory of the
+///   [chain registry](https://github.com/cosmos/chain-registry).
+pub async fn get_testnet_chain(name: &str) -> Result<Option<ChainInfo>> {
+    RegistryClient::default().get_testnet_chain(name).await
+}
+
+/// Retrieves the deserialized IBC path json in `testnets/_IBC` for a given pair of testnets. See [`get_path`].
+pub async fn get_testnet_path(chain_a: &str, chain_b: &str) -> Result<Option<IBCPath>> {
+    RegistryClient::default()
+        .get_testnet_path(chain_a, chain_b)
         .await
-        .wrap_err("error getting remote file content")
 }
 
-async fn parse_json<T>(data: String) -> Option<T>
-where
-    T: core::fmt::Debug + DeserializeOwned,
-{
-    let result = serde_json::from_str(&data);
-    println!("{:?}", result);
+/// Gets a list of the chains outside the Cosmos ecosystem with an asset list in the `_non-cosmos` directory
+pub async fn list_non_cosmos_chains() -> Result<Vec<String>> {
+    RegistryClient::default().list_non_cosmos_chains().await
+}
+
+/// Retrieves the deserialized `assetlist.json` for a chain in the `_non-cosmos` directory. See
+/// [`RegistrySource::get_non_cosmos_assets`].
+pub async fn get_non_cosmos_assets(name: &str) -> Result<Option<AssetList>> {
+    RegistryClient::default().get_non_cosmos_assets(name).await
+}
+
+/// Yields the deserialized `assetlist.json` of every chain in the registry as soon as each has been fetched and parsed.
+/// See [`RegistrySource::stream_asset_lists`].
+pub async fn stream_asset_lists() -> Result<BoxStream<'static, (String, Result<AssetList>)>> {
+    let client = Arc::new(RegistryClient::default());
+    let names = client.list_chains().await?;
+
+    Ok(load_unordered(names, DEFAULT_CONCURRENCY, move |name| {
+        let client = client.clone();
+        async move { read_json(&*client, assets_file(&name)).await }
+    }))
+}
+
+/// Yields the deserialized `chain.json` of every chain in the registry as soon as each has been fetched and parsed.
+/// See [`RegistrySource::stream_chains`].
+pub async fn stream_chains() -> Result<BoxStream<'static, (String, Result<ChainInfo>)>> {
+    let client = Arc::new(RegistryClient::default());
+    let names = client.list_chains().await?;
+
+    Ok(load_unordered(names, DEFAULT_CONCURRENCY, move |name| {
+        let client = client.clone();
+        async move { read_json(&*client, chain_file(&name)).await }
+    }))
+}
+
+/// Yields every deserialized IBC path in the registry as soon as each has been fetched and parsed. See
+/// [`RegistrySource::stream_paths`].
+pub async fn stream_paths() -> Result<BoxStream<'static, (String, Result<IBCPath>)>> {
+    let client = Arc::new(RegistryClient::default());
+    let names = client.list_paths().await?;
 
-    result.ok()
+    Ok(load_unordered(names, DEFAULT_CONCURRENCY, move |name| {
+        let client = client.clone();
+        async move { read_json(&*client, format!("_IBC/{}.json", name)).await }
+    }))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::source::parse_json;
     use assay::assay;
 
     #[assay]
     async fn gets_content_from_registry() {
-        let result = get_file_content(GIT_REF, "cosmoshub/chain.json").await;
+        let result = RegistryClient::default()
+            .get_file_content("cosmoshub/chain.json")
+            .await;
 
         result.unwrap();
     }
 
     #[assay]
     async fn parses_chain_info() {
-        let result = get_file_content(GIT_REF, "cosmoshub/chain.json")
+        let result = RegistryClient::default()
+            .get_file_content("cosmoshub/chain.json")
             .await
             .unwrap();
-        let result = parse_json::<ChainInfo>(result).await;
+        let result = parse_json::<ChainInfo>("cosmoshub/chain.json", &result);
 
         result.unwrap();
     }
@@ -155,7 +173,7 @@ mod tests {
     #[assay]
     async fn lists_paths() {
         let paths = list_paths().await.unwrap();
-        assert!(paths.len() > 0);
+        assert!(!paths.is_empty());
         paths
             .iter()
             .for_each(|path| assert!(!path.ends_with(".json")))
@@ -1,6 +1,7 @@
 //! Types for deserializing github repos API content
 use serde::Deserialize;
 use serde::Serialize;
+use sha1::{Digest, Sha1};
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(default, rename_all = "camelCase")]
@@ -30,3 +31,66 @@ pub struct Links {
     pub git: String,
     pub html: String,
 }
+
+/// A commit as returned by the GitHub commits API. Only the fields needed to pin a ref are modeled.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Commit {
+    pub sha: String,
+    pub url: String,
+    #[serde(rename = "html_url")]
+    pub html_url: String,
+}
+
+/// A git tree as returned by the GitHub git trees API
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Tree {
+    pub sha: String,
+    pub url: String,
+    pub tree: Vec<TreeEntry>,
+    /// Set by GitHub when the tree had more entries than it would return in a single response
+    pub truncated: bool,
+}
+
+/// An entry in a git tree. `type_field` is `blob` for files and `tree` for directories.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TreeEntry {
+    pub path: String,
+    pub mode: String,
+    #[serde(rename = "type")]
+    pub type_field: String,
+    pub sha: String,
+    /// Only present for blobs
+    pub size: Option<u64>,
+    pub url: String,
+}
+
+/// Computes the SHA git identifies a file's content by, as listed for each blob in a [`Tree`]
+pub fn git_blob_sha(data: &[u8]) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(format!("blob {}\0", data.len()));
+    hasher.update(data);
+
+    hex::encode(hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assay::assay;
+
+    #[assay]
+    fn computes_git_blob_shas() {
+        // as printed by `git hash-object`
+        assert_eq!(
+            git_blob_sha(b""),
+            "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"
+        );
+        assert_eq!(
+            git_blob_sha(b"hello\n"),
+            "ce013625030ba8dba906f756967f9e9ca394464a"
+        );
+    }
+}
@@ -68,18 +68,62 @@
 //! }
 //! ```
 
+/// Loading the whole registry from a single tarball
+pub mod archive;
+
+/// Synchronous wrappers for programs without an async runtime
+pub mod blocking;
+
 /// Models for assets.json ser/de
 pub mod assets;
 
 /// Models for chain.json ser/de
 pub mod chain;
 
+/// A configurable client for retrieving data from the registry at any git ref
+pub mod client;
+
 /// A cache type for reading IBC path data into memory for faster and filterable queries
 pub mod cache;
 
+/// A persistent on-disk cache for registry responses
+pub mod disk_cache;
+
+/// Downloading and verifying chain binaries
+pub(crate) mod download;
+
+/// Field-level diagnostics for registry files that don't match the models
+pub mod diagnostics;
+
+/// The error type returned by this crate
+pub mod error;
+
 /// API for getting and listing data from the registry Github repo
 pub mod get;
 pub mod github;
 
+/// Reads registry data from a local checkout of the registry repository
+pub mod local;
+
+/// An in-memory registry for tests and fixtures
+pub mod memory;
+
 /// Modles for IBC path JSON ser/de
 pub mod paths;
+
+/// GitHub rate limit tracking
+pub mod rate_limit;
+
+/// Retry policies for transient request failures
+pub mod retry;
+
+/// Serde helpers shared by the models
+pub(crate) mod serde_util;
+
+/// A trait abstracting where registry data is read from
+pub mod source;
+
+#[cfg(test)]
+pub(crate) mod test_server;
+
+pub use crate::{client::RegistryClient, error::RegistryError, source::RegistrySource};
@@ -0,0 +1,191 @@
+//! Reads registry data from a local checkout of the registry repository, for environments without access to GitHub
+use crate::{
+    error::{RegistryError, Result},
+    source::{DirEntry, RegistrySource},
+};
+use async_trait::async_trait;
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+use tokio::fs;
+
+/// Retrieves data from a clone of the [chain registry](https://github.com/cosmos/chain-registry) on the local
+/// filesystem.
+///
+/// # Examples
+///
+/// ```ignore
+/// use chain_registry::local::LocalRegistry;
+///
+/// let registry = LocalRegistry::new("/srv/chain-registry");
+/// let chain = registry.get_chain("osmosis").await?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct LocalRegistry {
+    root: PathBuf,
+}
+
+impl LocalRegistry {
+    /// Creates a registry reading from `root`, which should be the root directory of a registry checkout
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        LocalRegistry { root: root.into() }
+    }
+
+    /// The root directory of the registry checkout
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+}
+
+#[async_trait]
+impl RegistrySource for LocalRegistry {
+    async fn list_dir(&self, dir: &str) -> Result<Vec<DirEntry>> {
+        let mut listing = Vec::new();
+        let path = self.root.join(dir);
+        let mut entries = match fs::read_dir(&path).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                return Err(RegistryError::NotFound {
+                    path: dir.to_string(),
+                })
+            }
+            Err(e) => return Err(RegistryError::io(path.display().to_string(), e)),
+        };
+        let io_err = |e| RegistryError::io(path.display().to_string(), e);
+
+        while let Some(entry) = entries.next_entry().await.map_err(io_err)? {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let file_type = entry.file_type().await.map_err(io_err)?;
+
+            if file_type.is_dir() {
+                listing.push(DirEntry::dir(name));
+            } else if file_type.is_file() {
+                let size = entry.metadata().await.map_err(io_err)?.len();
+                listing.push(DirEntry::file(name).with_size(size));
+            }
+        }
+        listing.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(listing)
+    }
+
+    async fn get_file_content(&self, path: &str) -> Result<String> {
+        match fs::read_to_string(self.root.join(path)).await {
+            Ok(data) => Ok(data),
+            Err(e) if e.kind() == ErrorKind::NotFound => Err(RegistryError::NotFound {
+                path: path.to_string(),
+            }),
+            Err(e) => Err(RegistryError::io(path, e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assay::assay;
+
+    fn fixture() -> LocalRegistry {
+        LocalRegistry::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/registry"
+        ))
+    }
+
+    #[assay]
+    async fn lists_chains_skipping_special_dirs() {
+        let chains = fixture().list_chains().await.unwrap();
+
+        assert_eq!(chains, vec!["cosmoshub", "juno", "osmosis"]);
+    }
+
+    #[assay]
+    async fn lists_paths() {
+        let paths = fixture().list_paths().await.unwrap();
+
+        assert_eq!(paths, vec!["cosmoshub-osmosis", "juno-osmosis"]);
+    }
+
+    #[assay]
+    async fn reads_testnets() {
+        let registry = fixture();
+        let chain = registry
+            .get_testnet_chain("osmosistestnet")
+            .await
+            .unwrap()
+            .unwrap();
+        let assets = registry
+            .get_testnet_assets("osmosistestnet")
+            .await
+            .unwrap()
+            .unwrap();
+        let path = registry
+            .get_testnet_path("osmosistestnet", "junotestnet")
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            registry.list_testnets().await.unwrap(),
+            vec!["junotestnet", "osmosistestnet"]
+        );
+        assert_eq!(
+            registry.list_testnet_paths().await.unwrap(),
+            vec!["junotestnet-osmosistestnet"]
+        );
+        assert_eq!(chain.chain_id, "osmo-test-5");
+        assert_eq!(assets.assets[0].base, "uosmo");
+        assert_eq!(path.chain_1.chain_name, "junotestnet");
+        // mainnet getters don't see testnets
+        assert!(registry.get_chain("osmosistestnet").await.is_err());
+    }
+
+    #[assay]
+    async fn gets_chain_and_assets() {
+        let registry = fixture();
+        let chain = registry.get_chain("cosmoshub").await.unwrap().unwrap();
+        let assets = registry.get_assets("cosmoshub").await.unwrap().unwrap();
+
+        assert_eq!(chain.chain_id, "cosmoshub-4");
+        assert_eq!(assets.assets[0].base, "uatom");
+    }
+
+    #[assay]
+    async fn gets_path_out_of_order() {
+        let result = fixture()
+            .get_path("osmosis", "cosmoshub")
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(result.chain_1.chain_name, "cosmoshub");
+        assert_eq!(result.chain_2.chain_name, "osmosis");
+    }
+
+    #[assay]
+    async fn missing_file_errors() {
+        let err = fixture().get_assets("juno").await.unwrap_err();
+
+        assert!(err.is_not_found());
+    }
+
+    #[assay]
+    async fn streams_asset_lists() {
+        use futures::StreamExt;
+
+        let registry = fixture();
+        let mut results: Vec<_> = registry
+            .stream_asset_lists(2)
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        results.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].1.as_ref().unwrap().assets[0].base, "uatom");
+        assert!(results[1].1.as_ref().unwrap_err().is_not_found());
+        assert_eq!(results[2].1.as_ref().unwrap().chain_name, "osmosis");
+    }
+}
@@ -0,0 +1,257 @@
+//! An in-memory registry, useful as a test fixture or for serving registry files obtained by other means
+use crate::{
+    error::{RegistryError, Result},
+    source::{DirEntry, RegistrySource},
+};
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+
+/// Serves registry files from memory. Files are keyed by their path relative to the root of the registry and
+/// directories are implied by those paths.
+///
+/// # Examples
+///
+/// ```ignore
+/// use chain_registry::{memory::MemoryRegistry, RegistrySource};
+///
+/// let registry = MemoryRegistry::new()
+///     .with_file("osmosis/chain.json", r#"{"chain_name": "osmosis", "chain_id": "osmosis-1"}"#);
+///
+/// assert_eq!(registry.list_chains().await?, vec!["osmosis"]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MemoryRegistry {
+    files: BTreeMap<String, String>,
+}
+
+impl MemoryRegistry {
+    /// Creates an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a file to the registry, replacing any existing file at the same path
+    pub fn with_file(mut self, path: impl Into<String>, content: impl Into<String>) -> Self {
+        self.insert(path, content);
+        self
+    }
+
+    /// Adds a file to the registry, returning the previous content at that path if there was any
+    pub fn insert(
+        &mut self,
+        path: impl Into<String>,
+        content: impl Into<String>,
+    ) -> Option<String> {
+        self.files.insert(path.into(), content.into())
+    }
+
+    /// Removes a file from the registry, returning its content if it was present
+    pub fn remove(&mut self, path: &str) -> Option<String> {
+        self.files.remove(path)
+    }
+
+    /// An iterator over the paths of every file in the registry
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.files.keys().map(String::as_str)
+    }
+}
+
+impl<P, C> FromIterator<(P, C)> for MemoryRegistry
+where
+    P: Into<String>,
+    C: Into<String>,
+{
+    fn from_iter<I: IntoIterator<Item = (P, C)>>(iter: I) -> Self {
+        MemoryRegistry {
+            files: iter
+                .into_iter()
+                .map(|(p, c)| (p.into(), c.into()))
+                .collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl RegistrySource for MemoryRegistry {
+    async fn list_dir(&self, dir: &str) -> Result<Vec<DirEntry>> {
+        let prefix = if dir.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", dir.trim_end_matches('/'))
+        };
+        let mut listing: Vec<DirEntry> = Vec::new();
+
+        for (path, content) in self
+            .files
+            .iter()
+            .filter_map(|(p, c)| Some((p.strip_prefix(&prefix)?, c)))
+        {
+            let entry = match path.split_once('/') {
+                Some((name, _)) => DirEntry::dir(name),
+                None => DirEntry::file(path).with_size(content.len() as u64),
+            };
+
+            // keys are sorted so all files under the same directory are adjacent
+            if listing.last() != Some(&entry) {
+                listing.push(entry);
+            }
+        }
+
+        if listing.is_empty() && !dir.is_empty() {
+            return Err(RegistryError::NotFound {
+                path: dir.to_string(),
+            });
+        }
+
+        Ok(listing)
+    }
+
+    async fn get_file_content(&self, path: &str) -> Result<String> {
+        self.files
+            .get(path)
+            .cloned()
+            .ok_or_else(|| RegistryError::NotFound {
+                path: path.to_string(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics::ParseMode;
+    use assay::assay;
+
+    fn fixture() -> MemoryRegistry {
+        MemoryRegistry::new()
+            .with_file(".github/workflows/validate.yml", "")
+            .with_file(
+                "_IBC/cosmoshub-osmosis.json",
+                r#"{"chain_1": {"chain_name": "cosmoshub"}, "chain_2": {"chain_name": "osmosis"}}"#,
+            )
+            .with_file("_IBC/README.md", "")
+            .with_file(
+                "cosmoshub/chain.json",
+                r#"{"chain_name": "cosmoshub", "chain_id": "cosmoshub-4"}"#,
+            )
+            .with_file("cosmoshub/images/atom.png", "")
+            .with_file(
+                "osmosis/chain.json",
+                r#"{"chain_name": "osmosis", "chain_id": "osmosis-1"}"#,
+            )
+            .with_file("chain.schema.json", "{}")
+    }
+
+    #[assay]
+    async fn lists_chains_and_paths() {
+        let registry = fixture();
+
+        assert_eq!(
+            registry.list_chains().await.unwrap(),
+            vec!["cosmoshub", "osmosis"]
+        );
+        assert_eq!(
+            registry.list_paths().await.unwrap(),
+            vec!["cosmoshub-osmosis"]
+        );
+    }
+
+    #[assay]
+    async fn lists_nested_dirs_once() {
+        let listing = fixture().list_dir("cosmoshub").await.unwrap();
+
+        assert_eq!(
+            listing,
+            vec![
+                DirEntry::file("chain.json").with_size(54),
+                DirEntry::dir("images")
+            ]
+        );
+    }
+
+    #[assay]
+    async fn gets_chain_and_path() {
+        let registry = fixture();
+        let chain = registry.get_chain("osmosis").await.unwrap().unwrap();
+        let path = registry
+            .get_path("osmosis", "cosmoshub")
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(chain.chain_id, "osmosis-1");
+        assert_eq!(path.chain_1.chain_name, "cosmoshub");
+        assert!(registry.get_chain("juno").await.unwrap_err().is_not_found());
+    }
+
+    #[assay]
+    async fn traces_assets_to_non_cosmos_origin() {
+        let registry = MemoryRegistry::new()
+            .with_file(
+                "osmosis/assetlist.json",
+                r#"{"chain_name": "osmosis", "assets": [{"base": "ibc/EA1D", "traces": [
+                    {"type": "ibc", "counterparty": {"chain_name": "axelar", "base_denom": "weth-wei", "channel_id": "channel-3"},
+                     "chain": {"channel_id": "channel-208", "path": "transfer/channel-208/weth-wei"}}
+                ]}]}"#,
+            )
+            .with_file(
+                "axelar/assetlist.json",
+                r#"{"chain_name": "axelar", "assets": [{"base": "weth-wei", "traces": [
+                    {"type": "bridge", "counterparty": {"chain_name": "ethereum", "base_denom": "0xc02a"}, "provider": "Axelar"}
+                ]}]}"#,
+            )
+            .with_file(
+                "_non-cosmos/ethereum/assetlist.json",
+                r#"{"chain_name": "ethereum", "assets": [{"base": "0xc02a", "symbol": "WETH"}]}"#,
+            )
+            .with_file("_non-cosmos/_template/assetlist.json", "{}");
+
+        let hops = registry.trace_asset("osmosis", "ibc/EA1D").await.unwrap();
+        let chains: Vec<_> = hops.iter().map(|(chain, _)| chain.as_str()).collect();
+
+        assert_eq!(chains, vec!["osmosis", "axelar", "ethereum"]);
+        assert_eq!(hops[2].1.symbol, "WETH");
+        assert_eq!(hops[1].1.traces[0].provider.as_deref(), Some("Axelar"));
+        assert_eq!(
+            registry.list_non_cosmos_chains().await.unwrap(),
+            vec!["ethereum"]
+        );
+        assert!(registry
+            .get_non_cosmos_assets("ethereum")
+            .await
+            .unwrap()
+            .is_some());
+        // the asset list exists but doesn't have the denom
+        let err = registry.trace_asset("axelar", "uaxl").await.unwrap_err();
+        assert!(err.is_not_found());
+    }
+
+    #[assay]
+    async fn malformed_file_errors() {
+        let registry = MemoryRegistry::new().with_file("juno/chain.json", r#"{"chain_id": 4}"#);
+        let err = registry.get_chain("juno").await.unwrap_err();
+
+        assert!(matches!(err, RegistryError::Parse { ref path, .. } if path == "juno/chain.json"));
+    }
+
+    #[assay]
+    async fn checks_chain_against_model() {
+        let registry = MemoryRegistry::new().with_file(
+            "juno/chain.json",
+            r#"{"chain_name": "juno", "chain_id": "juno-1", "bech32_prefix": "juno", "slip44": 118, "mintscan": "juno"}"#,
+        );
+        let parsed = registry
+            .get_chain_checked("juno", ParseMode::Lenient)
+            .await
+            .unwrap();
+
+        assert_eq!(parsed.value.chain_id, "juno-1");
+        assert_eq!(parsed.diagnostics[0].to_string(), "mintscan: unknown field");
+
+        let err = registry
+            .get_chain_checked("juno", ParseMode::Strict)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, RegistryError::Schema { .. }));
+    }
+}
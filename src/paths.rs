@@ -1,64 +1,113 @@
 /// Models for serializing and deserializing IBC path JSON data found in the `_IBC/` directory of the registry repository
+use crate::serde_util::{models, Present};
 use serde::Deserialize;
 use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+models!(
+    IBCPath,
+    Chain1,
+    Chain2,
+    Channel,
+    ChannelChain1,
+    ChannelChain2,
+    Tags
+);
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(default, rename_all = "snake_case")]
+#[serde(default, remote = "Self", rename_all = "snake_case")]
 pub struct IBCPath {
     #[serde(rename = "$schema")]
     pub schema: String,
     pub chain_1: Chain1,
     pub chain_2: Chain2,
     pub channels: Vec<Channel>,
+    /// Keys not covered by the fields above. Written back on serialization.
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(default, rename_all = "snake_case")]
+#[serde(default, remote = "Self", rename_all = "snake_case")]
 pub struct Chain1 {
     pub chain_name: String,
     pub client_id: String,
     pub connection_id: String,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(default, rename_all = "snake_case")]
+#[serde(default, remote = "Self", rename_all = "snake_case")]
 pub struct Chain2 {
     pub chain_name: String,
     pub client_id: String,
     pub connection_id: String,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(default, rename_all = "snake_case")]
+#[serde(default, remote = "Self", rename_all = "snake_case")]
 pub struct Channel {
     pub chain_1: ChannelChain1,
     pub chain_2: ChannelChain2,
     pub ordering: String,
     pub version: String,
     pub tags: Tags,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(default, rename_all = "snake_case")]
+#[serde(default, remote = "Self", rename_all = "snake_case")]
 pub struct ChannelChain1 {
     pub channel_id: String,
     pub port_id: String,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(default, rename_all = "snake_case")]
+#[serde(default, remote = "Self", rename_all = "snake_case")]
 pub struct ChannelChain2 {
     pub channel_id: String,
     pub port_id: String,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(default, rename_all = "snake_case")]
+#[serde(default, remote = "Self", rename_all = "snake_case")]
 pub struct Tags {
     pub dex: String,
     pub preferred: bool,
     pub properties: String,
     pub status: String,
+    #[serde(skip)]
+    pub extras: BTreeMap<String, Value>,
+    #[doc(hidden)]
+    #[serde(skip)]
+    pub present: Present,
 }
 
 /// Represents an IBC path tag
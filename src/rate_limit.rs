@@ -0,0 +1,128 @@
+//! Parsing of the rate limit headers GitHub attaches to API responses
+use http::{HeaderMap, StatusCode};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const LIMIT_HEADER: &str = "x-ratelimit-limit";
+const REMAINING_HEADER: &str = "x-ratelimit-remaining";
+const RESET_HEADER: &str = "x-ratelimit-reset";
+const RETRY_AFTER_HEADER: &str = "retry-after";
+// used when GitHub reports an exhausted limit without saying when it resets
+const DEFAULT_RESET_DELAY: Duration = Duration::from_secs(60);
+
+/// The state of the caller's GitHub rate limit as of the most recent response
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimit {
+    /// The maximum number of requests allowed in the current window
+    pub limit: Option<u64>,
+    /// The number of requests remaining in the current window
+    pub remaining: Option<u64>,
+    /// When the current window resets
+    pub reset: Option<SystemTime>,
+}
+
+impl RateLimit {
+    /// Parses the `X-RateLimit-*` headers of a response. Returns `None` if none of them are present.
+    pub fn from_headers(headers: &HeaderMap) -> Option<RateLimit> {
+        let limit = header_u64(headers, LIMIT_HEADER);
+        let remaining = header_u64(headers, REMAINING_HEADER);
+        let reset = header_u64(headers, RESET_HEADER).map(|s| UNIX_EPOCH + Duration::from_secs(s));
+
+        if limit.is_none() && remaining.is_none() && reset.is_none() {
+            return None;
+        }
+
+        Some(RateLimit {
+            limit,
+            remaining,
+            reset,
+        })
+    }
+
+    /// Whether there are no requests remaining in the current window
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining == Some(0)
+    }
+}
+
+/// If a response was rejected by a primary or secondary rate limit, returns when the limit resets
+pub(crate) fn rate_limit_reset(status: StatusCode, headers: &HeaderMap) -> Option<SystemTime> {
+    if status != StatusCode::FORBIDDEN && status != StatusCode::TOO_MANY_REQUESTS {
+        return None;
+    }
+
+    // secondary rate limits report how long to back off instead of a reset time
+    if let Some(secs) = header_u64(headers, RETRY_AFTER_HEADER) {
+        return Some(SystemTime::now() + Duration::from_secs(secs));
+    }
+
+    match RateLimit::from_headers(headers) {
+        Some(rl) if rl.is_exhausted() => Some(
+            rl.reset
+                .unwrap_or_else(|| SystemTime::now() + DEFAULT_RESET_DELAY),
+        ),
+        _ => None,
+    }
+}
+
+fn header_u64(headers: &HeaderMap, name: &str) -> Option<u64> {
+    headers.get(name)?.to_str().ok()?.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assay::assay;
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (k, v) in pairs {
+            map.insert(*k, v.parse().unwrap());
+        }
+        map
+    }
+
+    #[assay]
+    fn parses_rate_limit_headers() {
+        let map = headers(&[
+            ("X-RateLimit-Limit", "60"),
+            ("X-RateLimit-Remaining", "0"),
+            ("X-RateLimit-Reset", "1700000000"),
+        ]);
+        let rl = RateLimit::from_headers(&map).unwrap();
+
+        assert_eq!(rl.limit, Some(60));
+        assert_eq!(rl.remaining, Some(0));
+        assert_eq!(
+            rl.reset,
+            Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000))
+        );
+        assert!(rl.is_exhausted());
+        assert!(RateLimit::from_headers(&HeaderMap::new()).is_none());
+    }
+
+    #[assay]
+    fn detects_exhausted_limit() {
+        let map = headers(&[
+            ("X-RateLimit-Remaining", "0"),
+            ("X-RateLimit-Reset", "1700000000"),
+        ]);
+
+        assert_eq!(
+            rate_limit_reset(StatusCode::FORBIDDEN, &map),
+            Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000))
+        );
+        // a 403 with requests remaining is a permissions problem, not a rate limit
+        let map = headers(&[("X-RateLimit-Remaining", "12")]);
+        assert_eq!(rate_limit_reset(StatusCode::FORBIDDEN, &map), None);
+        assert_eq!(rate_limit_reset(StatusCode::OK, &map), None);
+    }
+
+    #[assay]
+    fn honors_retry_after() {
+        let map = headers(&[("Retry-After", "30")]);
+        let reset = rate_limit_reset(StatusCode::TOO_MANY_REQUESTS, &map).unwrap();
+        let wait = reset.duration_since(SystemTime::now()).unwrap();
+
+        assert!(wait <= Duration::from_secs(30) && wait > Duration::from_secs(25));
+    }
+}
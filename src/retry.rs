@@ -0,0 +1,157 @@
+//! Retrying registry requests that fail for transient reasons
+use http::StatusCode;
+use rand::Rng;
+use std::time::Duration;
+
+/// Controls how [`RegistryClient`](crate::client::RegistryClient) retries requests that fail with a timeout, a
+/// connection error or a retryable status code. Delays grow exponentially from `base_delay`, capped at `max_delay`.
+/// A `404 Not Found` is never retried.
+///
+/// # Examples
+///
+/// ```ignore
+/// use chain_registry::{retry::RetryPolicy, RegistryClient};
+/// use std::time::Duration;
+///
+/// let policy = RetryPolicy {
+///     max_attempts: 5,
+///     base_delay: Duration::from_secs(1),
+///     ..Default::default()
+/// };
+/// let client = RegistryClient::new().with_retry_policy(policy);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryPolicy {
+    /// The maximum number of times a request is sent, including the first attempt. `1` disables retries.
+    pub max_attempts: u32,
+    /// The delay before the first retry. Each subsequent retry doubles it.
+    pub base_delay: Duration,
+    /// The upper bound for the delay between attempts
+    pub max_delay: Duration,
+    /// The fraction of each delay, between `0.0` and `1.0`, that is randomized to spread out retries from concurrent
+    /// requests. With a jitter of `0.5` a 2 second delay becomes anything between 1 and 2 seconds. Values outside that
+    /// range are clamped to it and `NaN` disables jitter.
+    pub jitter: f64,
+    /// Response statuses that are considered transient
+    pub retryable_statuses: Vec<StatusCode>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: 0.5,
+            retryable_statuses: vec![
+                StatusCode::REQUEST_TIMEOUT,
+                StatusCode::INTERNAL_SERVER_ERROR,
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ],
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that sends every request exactly once
+    pub fn none() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Whether a response with `status` should be retried
+    pub fn is_retryable_status(&self, status: StatusCode) -> bool {
+        status != StatusCode::NOT_FOUND && self.retryable_statuses.contains(&status)
+    }
+
+    /// The delay before the given retry, where `1` is the first retry
+    pub fn delay(&self, retry: u32) -> Duration {
+        let exp = retry.saturating_sub(1).min(31);
+        let delay = self.base_delay.saturating_mul(1 << exp).min(self.max_delay);
+        // out of range jitter is clamped and NaN disables it, since either would make `mul_f64` panic
+        let jitter = if self.jitter.is_nan() {
+            0.0
+        } else {
+            self.jitter.clamp(0.0, 1.0)
+        };
+
+        if jitter == 0.0 {
+            return delay;
+        }
+
+        delay.mul_f64(1.0 - jitter * rand::thread_rng().gen::<f64>())
+    }
+}
+
+/// Whether a request error is likely to succeed if retried
+pub(crate) fn is_retryable_error(err: &reqwest::Error) -> bool {
+    err.is_timeout() || err.is_connect() || err.is_request() || err.is_body()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assay::assay;
+
+    #[assay]
+    fn delays_grow_exponentially() {
+        let policy = RetryPolicy {
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+            jitter: 0.0,
+            ..Default::default()
+        };
+
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(2), Duration::from_millis(200));
+        assert_eq!(policy.delay(3), Duration::from_millis(400));
+        assert_eq!(policy.delay(5), Duration::from_secs(1));
+        assert_eq!(policy.delay(100), Duration::from_secs(1));
+    }
+
+    #[assay]
+    fn jitter_stays_within_bounds() {
+        let policy = RetryPolicy {
+            base_delay: Duration::from_millis(1000),
+            jitter: 0.5,
+            ..Default::default()
+        };
+
+        for _ in 0..100 {
+            let delay = policy.delay(1);
+            assert!(delay >= Duration::from_millis(500) && delay <= Duration::from_millis(1000));
+        }
+    }
+
+    #[assay]
+    fn invalid_jitter_is_clamped() {
+        let delay = |jitter| {
+            RetryPolicy {
+                base_delay: Duration::from_millis(1000),
+                jitter,
+                ..Default::default()
+            }
+            .delay(1)
+        };
+
+        assert_eq!(delay(f64::NAN), Duration::from_millis(1000));
+        assert_eq!(delay(-1.0), Duration::from_millis(1000));
+        assert!(delay(f64::INFINITY) <= Duration::from_millis(1000));
+    }
+
+    #[assay]
+    fn never_retries_not_found() {
+        let policy = RetryPolicy {
+            retryable_statuses: vec![StatusCode::NOT_FOUND, StatusCode::BAD_GATEWAY],
+            ..Default::default()
+        };
+
+        assert!(!policy.is_retryable_status(StatusCode::NOT_FOUND));
+        assert!(policy.is_retryable_status(StatusCode::BAD_GATEWAY));
+        assert!(!RetryPolicy::default().is_retryable_status(StatusCode::FORBIDDEN));
+    }
+}
@@ -0,0 +1,551 @@
+//! Serde helpers shared by the registry models. Each model derives its field (de)serialization with
+//! `#[serde(remote = "Self")]` and implements the serde traits with [`models!`], which wraps the derived code so
+//! that:
+//!
+//! * keys the model has no field for are kept in its `extras` instead of being dropped
+//! * the keys a parsed file had are recorded, so that serializing it writes back the same keys. A field that was
+//!   absent is only written if it's been changed from its default.
+use serde::{
+    de::{
+        self,
+        value::{MapDeserializer, SeqDeserializer},
+        DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor,
+    },
+    forward_to_deserialize_any,
+    ser::{self, SerializeMap, SerializeStruct},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use serde_json::{Map, Value};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+/// The name of the newtype struct that the value of an unknown key is deserialized as. Deserializers treat newtype
+/// structs as their content, except for [`Strict`], which fails on this one.
+const UNKNOWN_FIELD: &str = "$chain_registry::UnknownField";
+
+/// The keys of a model's fields that were present in the file it was parsed from. Always compares equal so that it
+/// doesn't affect the equality of models.
+#[doc(hidden)]
+#[derive(Clone, Debug, Default)]
+pub struct Present(BTreeSet<&'static str>);
+
+impl PartialEq for Present {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for Present {}
+
+/// A model whose (de)serialization is wrapped by [`models!`]
+pub(crate) trait Model: Default {
+    fn present(&self) -> &Present;
+
+    fn extras(&self) -> &BTreeMap<String, Value>;
+
+    fn set_parsed(&mut self, present: Present, extras: BTreeMap<String, Value>);
+
+    /// The derived serialization of the model's fields
+    fn serialize_fields<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+}
+
+/// Implements [`Model`], `Deserialize` and `Serialize` for models that derive both with `#[serde(remote = "Self")]`
+/// and have `extras` and `present` fields
+macro_rules! models {
+    ($($model:ident),* $(,)?) => {$(
+        impl $crate::serde_util::Model for $model {
+            fn present(&self) -> &$crate::serde_util::Present {
+                &self.present
+            }
+
+            fn extras(&self) -> &::std::collections::BTreeMap<String, ::serde_json::Value> {
+                &self.extras
+            }
+
+            fn set_parsed(
+                &mut self,
+                present: $crate::serde_util::Present,
+                extras: ::std::collections::BTreeMap<String, ::serde_json::Value>,
+            ) {
+                self.present = present;
+                self.extras = extras;
+            }
+
+            fn serialize_fields<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                $model::serialize(self, serializer)
+            }
+        }
+
+        impl<'de> ::serde::Deserialize<'de> for $model {
+            fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                $crate::serde_util::deserialize_model(deserializer, |d| $model::deserialize(d))
+            }
+        }
+
+        impl ::serde::Serialize for $model {
+            fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                $crate::serde_util::serialize_model(self, serializer)
+            }
+        }
+    )*};
+}
+
+pub(crate) use models;
+
+/// Deserializes a model with its derived `deserialize_fields`, recording which of its fields were present and
+/// collecting the keys it has no field for
+pub(crate) fn deserialize_model<'de, D, T, F>(
+    deserializer: D,
+    deserialize_fields: F,
+) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Model,
+    F: FnOnce(ModelDeserializer<'_, D>) -> Result<T, D::Error>,
+{
+    let mut seen = Seen::default();
+    let mut model = deserialize_fields(ModelDeserializer {
+        inner: deserializer,
+        seen: &mut seen,
+    })?;
+    model.set_parsed(seen.present, seen.extras);
+    Ok(model)
+}
+
+/// Serializes a model's fields as a map, leaving out those that were absent from the file it was parsed from and
+/// still have their default value, followed by its `extras`
+pub(crate) fn serialize_model<T, S>(model: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Model,
+    S: Serializer,
+{
+    model.serialize_fields(ModelSerializer {
+        inner: serializer,
+        model,
+    })
+}
+
+#[derive(Default)]
+struct Seen {
+    present: Present,
+    extras: BTreeMap<String, Value>,
+}
+
+/// Hands the derived `Deserialize` a map whose unknown keys have been moved into [`Seen::extras`]
+pub(crate) struct ModelDeserializer<'a, D> {
+    inner: D,
+    seen: &'a mut Seen,
+}
+
+impl<'de, 'a, D: Deserializer<'de>> Deserializer<'de> for ModelDeserializer<'a, D> {
+    type Error = D::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, D::Error> {
+        self.inner.deserialize_any(visitor)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, D::Error> {
+        self.inner.deserialize_struct(
+            name,
+            fields,
+            ModelVisitor {
+                visitor,
+                fields,
+                seen: self.seen,
+            },
+        )
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf option unit unit_struct
+        newtype_struct seq tuple tuple_struct map enum identifier ignored_any
+    }
+}
+
+struct ModelVisitor<'a, V> {
+    visitor: V,
+    fields: &'static [&'static str],
+    seen: &'a mut Seen,
+}
+
+impl<'de, 'a, V: Visitor<'de>> Visitor<'de> for ModelVisitor<'a, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.visitor.expecting(f)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<V::Value, A::Error> {
+        self.visitor.visit_seq(seq)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<V::Value, A::Error> {
+        self.visitor.visit_map(ModelMap {
+            map,
+            fields: self.fields,
+            seen: self.seen,
+        })
+    }
+}
+
+struct ModelMap<'a, A> {
+    map: A,
+    fields: &'static [&'static str],
+    seen: &'a mut Seen,
+}
+
+impl<'de, 'a, A: MapAccess<'de>> MapAccess<'de> for ModelMap<'a, A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, A::Error> {
+        while let Some(key) = self.map.next_key::<String>()? {
+            if let Some(field) = self.fields.iter().find(|field| **field == key) {
+                self.seen.present.0.insert(field);
+                return seed.deserialize(key.into_deserializer()).map(Some);
+            }
+            let value = self.map.next_value_seed(UnknownField)?;
+            self.seen.extras.insert(key, value);
+        }
+        Ok(None)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, A::Error> {
+        self.map.next_value_seed(seed)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.map.size_hint()
+    }
+}
+
+/// Hands the derived `Serialize` a serializer that writes the model's fields as a map, filtered by
+/// [`serialize_model`]
+struct ModelSerializer<'a, S, T> {
+    inner: S,
+    model: &'a T,
+}
+
+impl<'a, S: Serializer, T: Model> Serializer for ModelSerializer<'a, S, T> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = S::SerializeSeq;
+    type SerializeTuple = S::SerializeTuple;
+    type SerializeTupleStruct = S::SerializeTupleStruct;
+    type SerializeTupleVariant = S::SerializeTupleVariant;
+    type SerializeMap = S::SerializeMap;
+    type SerializeStruct = ModelFields<'a, S::SerializeMap, T>;
+    type SerializeStructVariant = S::SerializeStructVariant;
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, S::Error> {
+        // the derived fields of a default model, in which nested models are left empty
+        let defaults = match self.model_defaults()? {
+            Value::Object(defaults) => defaults,
+            _ => Map::new(),
+        };
+        Ok(ModelFields {
+            map: self.inner.serialize_map(None)?,
+            model: self.model,
+            defaults,
+        })
+    }
+
+    fn serialize_bool(self, v: bool) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_bool(v)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_i8(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_i16(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_i32(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_i64(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_u8(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_u16(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_u32(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_u64(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_f32(v)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_f64(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_char(v)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_str(v)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_bytes(v)
+    }
+
+    fn serialize_none(self) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_none()
+    }
+
+    fn serialize_some<V: ?Sized + Serialize>(self, value: &V) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_some(value)
+    }
+
+    fn serialize_unit(self) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_unit()
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_unit_struct(name)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<S::Ok, S::Error> {
+        self.inner
+            .serialize_unit_variant(name, variant_index, variant)
+    }
+
+    fn serialize_newtype_struct<V: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        value: &V,
+    ) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_newtype_struct(name, value)
+    }
+
+    fn serialize_newtype_variant<V: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &V,
+    ) -> Result<S::Ok, S::Error> {
+        self.inner
+            .serialize_newtype_variant(name, variant_index, variant, value)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<S::SerializeSeq, S::Error> {
+        self.inner.serialize_seq(len)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<S::SerializeTuple, S::Error> {
+        self.inner.serialize_tuple(len)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<S::SerializeTupleStruct, S::Error> {
+        self.inner.serialize_tuple_struct(name, len)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<S::SerializeTupleVariant, S::Error> {
+        self.inner
+            .serialize_tuple_variant(name, variant_index, variant, len)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<S::SerializeMap, S::Error> {
+        self.inner.serialize_map(len)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<S::SerializeStructVariant, S::Error> {
+        self.inner
+            .serialize_struct_variant(name, variant_index, variant, len)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+impl<'a, S: Serializer, T: Model> ModelSerializer<'a, S, T> {
+    fn model_defaults(&self) -> Result<Value, S::Error> {
+        T::default()
+            .serialize_fields(serde_json::value::Serializer)
+            .map_err(ser::Error::custom)
+    }
+}
+
+struct ModelFields<'a, M, T> {
+    map: M,
+    model: &'a T,
+    defaults: Map<String, Value>,
+}
+
+impl<'a, M: SerializeMap, T: Model> SerializeStruct for ModelFields<'a, M, T> {
+    type Ok = M::Ok;
+    type Error = M::Error;
+
+    fn serialize_field<V: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &V,
+    ) -> Result<(), M::Error> {
+        if !self.model.present().0.contains(key) {
+            let value = serde_json::to_value(value).map_err(ser::Error::custom)?;
+            if self.defaults.get(key) == Some(&value) {
+                return Ok(());
+            }
+        }
+        self.map.serialize_entry(key, value)
+    }
+
+    fn end(mut self) -> Result<M::Ok, M::Error> {
+        for (key, value) in self.model.extras() {
+            self.map.serialize_entry(key, value)?;
+        }
+        self.map.end()
+    }
+}
+
+struct UnknownField;
+
+impl<'de> DeserializeSeed<'de> for UnknownField {
+    type Value = Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_newtype_struct(UNKNOWN_FIELD, self)
+    }
+}
+
+impl<'de> Visitor<'de> for UnknownField {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("any value")
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Value, D::Error> {
+        Value::deserialize(deserializer)
+    }
+}
+
+/// The error [`Strict`] fails with on a key the model has no field for. Its path ends with the key.
+pub(crate) const UNKNOWN_FIELD_ERROR: &str = "unknown field";
+
+/// Deserializes a JSON value like `serde_json` does, except that a key a model has no field for fails with
+/// [`UNKNOWN_FIELD_ERROR`] instead of being kept in its `extras`
+pub(crate) struct Strict<'a>(pub &'a Value);
+
+impl<'de> Deserializer<'de> for Strict<'de> {
+    type Error = serde_json::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            Value::Array(items) => {
+                let mut seq = SeqDeserializer::new(items.iter().map(Strict));
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            Value::Object(fields) => {
+                let mut map =
+                    MapDeserializer::new(fields.iter().map(|(k, v)| (k.as_str(), Strict(v))));
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+            value => value.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        if name == UNKNOWN_FIELD {
+            return Err(de::Error::custom(UNKNOWN_FIELD_ERROR));
+        }
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.0.deserialize_enum(name, variants, visitor)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf unit unit_struct seq
+        tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, serde_json::Error> for Strict<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
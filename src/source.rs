@@ -0,0 +1,705 @@
+//! Abstracts where registry data is read from. Anything that can list a directory and read a file can serve the
+//! registry, whether that's GitHub, a local checkout, an in-memory fixture or a private mirror.
+use crate::{
+    assets::{Asset, AssetList},
+    chain::ChainInfo,
+    diagnostics::{self, ParseMode, Parsed},
+    error::{RegistryError, Result},
+    paths::IBCPath,
+};
+use async_trait::async_trait;
+use futures::{stream, stream::BoxStream, Future, Stream, StreamExt};
+use serde::de::DeserializeOwned;
+
+/// The default number of files fetched at once by the bulk getters
+pub const DEFAULT_CONCURRENCY: usize = 16;
+/// The directory holding the asset lists of chains outside the Cosmos ecosystem, e.g. Ethereum and Bitcoin
+pub const NON_COSMOS_DIR: &str = "_non-cosmos";
+
+/// The part of the registry a chain belongs to. Mainnets live in the root of the registry and testnets under
+/// `testnets/`, each with their own `_IBC` folder.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Network {
+    #[default]
+    Mainnet,
+    Testnet,
+}
+
+impl Network {
+    /// The directory the network's chains and `_IBC` folder live in, relative to the root of the registry
+    pub fn dir(self) -> &'static str {
+        match self {
+            Network::Mainnet => "",
+            Network::Testnet => "testnets",
+        }
+    }
+
+    /// Prefixes a path relative to the network's directory with that directory
+    fn join(self, path: &str) -> String {
+        match (self.dir(), path) {
+            ("", path) => path.to_string(),
+            (dir, "") => dir.to_string(),
+            (dir, path) => format!("{}/{}", dir, path),
+        }
+    }
+}
+
+/// The kind of an entry in a registry directory
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntryKind {
+    File,
+    Dir,
+}
+
+/// An entry in a registry directory
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DirEntry {
+    /// The name of the file or directory, without its parent path
+    pub name: String,
+    pub kind: EntryKind,
+    /// The git object SHA of the entry, if the source knows it
+    pub sha: Option<String>,
+    /// The size of the file in bytes, if the source knows it. Always `None` for directories.
+    pub size: Option<u64>,
+}
+
+impl DirEntry {
+    pub fn file(name: impl Into<String>) -> Self {
+        DirEntry {
+            name: name.into(),
+            kind: EntryKind::File,
+            sha: None,
+            size: None,
+        }
+    }
+
+    pub fn dir(name: impl Into<String>) -> Self {
+        DirEntry {
+            name: name.into(),
+            kind: EntryKind::Dir,
+            sha: None,
+            size: None,
+        }
+    }
+
+    /// Sets the git object SHA of the entry
+    pub fn with_sha(mut self, sha: impl Into<String>) -> Self {
+        self.sha = Some(sha.into());
+        self
+    }
+
+    /// Sets the size of the entry in bytes
+    pub fn with_size(mut self, size: u64) -> Self {
+        self.size = Some(size);
+        self
+    }
+}
+
+/// A source of chain registry data. Implementors provide directory listing and file reads; listing chains and paths
+/// and retrieving deserialized registry objects are provided on top of those, though implementors may override them
+/// if their backend can answer more efficiently.
+///
+/// # Examples
+///
+/// ```ignore
+/// use chain_registry::{RegistrySource, local::LocalRegistry, RegistryClient};
+///
+/// async fn chain_ids(source: &impl RegistrySource) -> chain_registry::error::Result<Vec<String>> {
+///     let mut ids = Vec::new();
+///     for name in source.list_chains().await? {
+///         if let Some(chain) = source.get_chain(&name).await? {
+///             ids.push(chain.chain_id);
+///         }
+///     }
+///     Ok(ids)
+/// }
+///
+/// let from_github = chain_ids(&RegistryClient::default()).await?;
+/// let from_disk = chain_ids(&LocalRegistry::new("/srv/chain-registry")).await?;
+/// ```
+#[async_trait]
+pub trait RegistrySource: Send + Sync {
+    /// Lists the entries of a directory in the registry
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The directory path relative to the root of the registry, e.g. `_IBC`. An empty string lists the root.
+    async fn list_dir(&self, dir: &str) -> Result<Vec<DirEntry>>;
+
+    /// Reads the raw content of a file in the registry
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file relative to the root of the registry, e.g. `cosmoshub/chain.json`
+    async fn get_file_content(&self, path: &str) -> Result<String>;
+
+    /// Gets a list of chain names from the registry
+    async fn list_chains(&self) -> Result<Vec<String>> {
+        Ok(self
+            .list_dir("")
+            .await?
+            .into_iter()
+            .filter(|e| e.kind == EntryKind::Dir && is_chain_dir(&e.name))
+            .map(|e| e.name)
+            .collect())
+    }
+
+    /// Gets a list of path names from the registry in the form <chain_a>-<chain_b>
+    async fn list_paths(&self) -> Result<Vec<String>> {
+        Ok(self
+            .list_dir("_IBC")
+            .await?
+            .into_iter()
+            .filter(|e| {
+                e.kind == EntryKind::File && !e.name.starts_with('_') && e.name.ends_with(".json")
+            })
+            .map(|e| e.name[..e.name.len() - ".json".len()].to_string())
+            .collect())
+    }
+
+    /// Gets a list of the chains outside the Cosmos ecosystem that have an asset list in the `_non-cosmos` directory,
+    /// e.g. `ethereum`. These chains have no `chain.json`.
+    async fn list_non_cosmos_chains(&self) -> Result<Vec<String>> {
+        Ok(self
+            .list_dir(NON_COSMOS_DIR)
+            .await?
+            .into_iter()
+            .filter(|e| e.kind == EntryKind::Dir && !e.name.starts_with('_'))
+            .map(|e| e.name)
+            .collect())
+    }
+
+    /// Retrieves the deserialized `_non-cosmos/<name>/assetlist.json` for a chain outside the Cosmos ecosystem. Returns
+    /// [`RegistryError::NotFound`] if there is no such asset list and [`RegistryError::Parse`] if it doesn't match the
+    /// [`AssetList`] model.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The chain name. Must match the name of the chain's folder in the `_non-cosmos` directory of the
+    ///   [chain registry](https://github.com/cosmos/chain-registry).
+    async fn get_non_cosmos_assets(&self, name: &str) -> Result<Option<AssetList>> {
+        read_json(self, non_cosmos_assets_file(name))
+            .await
+            .map(Some)
+    }
+
+    /// Follows an asset's traces back to the chain it originated on. Starting with the asset whose base denom is
+    /// `base_denom` on `chain_name`, the counterparty of each asset's last trace is looked up in the asset list of the
+    /// chain it was received from, in the root directory or in `_non-cosmos`, until an asset without traces is
+    /// reached. Returns every hop paired with its chain name, ending with the origin. Fails with
+    /// [`RegistryError::NotFound`] if an asset list or asset along the way is missing.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use chain_registry::{RegistryClient, RegistrySource};
+    ///
+    /// // axlWETH on Osmosis was bridged from Ethereum by Axelar
+    /// let hops = RegistryClient::new()
+    ///     .trace_asset("osmosis", "ibc/EA1D43981D5C9A1C4AAEA9C23BB1D4FA126BA9BC7020A25E0AE4AA841EA25DC5")
+    ///     .await?;
+    /// let (origin, asset) = hops.last().unwrap();
+    /// assert_eq!(origin, "ethereum");
+    /// ```
+    async fn trace_asset(
+        &self,
+        chain_name: &str,
+        base_denom: &str,
+    ) -> Result<Vec<(String, Asset)>> {
+        let mut hops: Vec<(String, Asset)> = Vec::new();
+        let mut next = (chain_name.to_string(), base_denom.to_string());
+
+        loop {
+            let (chain, base) = next;
+            let asset = any_asset_list(self, &chain)
+                .await?
+                .assets
+                .into_iter()
+                .find(|a| a.base == base)
+                .ok_or_else(|| RegistryError::NotFound {
+                    path: format!("{} in the asset list of {}", base, chain),
+                })?;
+            let counterparty = asset.traces.last().map(|t| {
+                (
+                    t.counterparty.chain_name.clone(),
+                    t.counterparty.base_denom.clone(),
+                )
+            });
+            hops.push((chain, asset));
+
+            next = match counterparty {
+                // stop at an asset that traces back to one already visited rather than looping forever
+                Some((c, b)) if !hops.iter().any(|(hc, ha)| *hc == c && ha.base == b) => (c, b),
+                _ => return Ok(hops),
+            };
+        }
+    }
+
+    /// A view of one network of this source. Every method of the returned source, including the bulk and streaming
+    /// getters, reads from that network's directory, so [`Network::Testnet`] reads `testnets/<chain>/chain.json` and
+    /// `testnets/_IBC`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use chain_registry::{source::Network, RegistryClient, RegistrySource};
+    ///
+    /// let client = RegistryClient::new();
+    /// let testnets = client.network(Network::Testnet);
+    /// let chains = testnets.get_chains(&testnets.list_chains().await?, 8).await;
+    /// ```
+    fn network(&self, network: Network) -> NetworkView<'_, Self> {
+        NetworkView {
+            source: self,
+            network,
+        }
+    }
+
+    /// Gets a list of testnet chain names from the `testnets` directory of the registry
+    async fn list_testnets(&self) -> Result<Vec<String>> {
+        self.network(Network::Testnet).list_chains().await
+    }
+
+    /// Gets a list of testnet path names from `testnets/_IBC` in the form <chain_a>-<chain_b>
+    async fn list_testnet_paths(&self) -> Result<Vec<String>> {
+        self.network(Network::Testnet).list_paths().await
+    }
+
+    /// Like [`get_assets`](RegistrySource::get_assets), but for a chain in the `testnets` directory
+    async fn get_testnet_assets(&self, name: &str) -> Result<Option<AssetList>> {
+        self.network(Network::Testnet).get_assets(name).await
+    }
+
+    /// Like [`get_chain`](RegistrySource::get_chain), but for a chain in the `testnets` directory
+    async fn get_testnet_chain(&self, name: &str) -> Result<Option<ChainInfo>> {
+        self.network(Network::Testnet).get_chain(name).await
+    }
+
+    /// Like [`get_path`](RegistrySource::get_path), but for a path in `testnets/_IBC`
+    async fn get_testnet_path(&self, chain_a: &str, chain_b: &str) -> Result<Option<IBCPath>> {
+        self.network(Network::Testnet)
+            .get_path(chain_a, chain_b)
+            .await
+    }
+
+    /// Retrieves the deserialized `assets.json` for a given chain. Returns [`RegistryError::NotFound`] if there is no
+    /// `assets.json` present and [`RegistryError::Parse`] if it doesn't match the [`AssetList`] model.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The chain name. Must match the name of the chain's folder in the root directory of the
+    ///   [chain registry](https://github.com/cosmos/chain-registry).
+    async fn get_assets(&self, name: &str) -> Result<Option<AssetList>> {
+        let path = assets_file(name);
+        let data = self.get_file_content(&path).await?;
+
+        parse_json(&path, &data).map(Some)
+    }
+
+    /// Retrieves the deserialized `chain.json` for a given chain. Returns [`RegistryError::NotFound`] if there is no
+    /// `chain.json` present and [`RegistryError::Parse`] if it doesn't match the [`ChainInfo`] model.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The chain name. Must match the name of the chain's folder in the root directory of the
+    ///   [chain registry](https://github.com/cosmos/chain-registry).
+    async fn get_chain(&self, name: &str) -> Result<Option<ChainInfo>> {
+        let path = chain_file(name);
+        let data = self.get_file_content(&path).await?;
+
+        parse_json(&path, &data).map(Some)
+    }
+
+    /// Retrieves the deserialized IBC path json for a given pair of chains. Returns [`RegistryError::NotFound`] if
+    /// there is no path present and [`RegistryError::Parse`] if it doesn't match the [`IBCPath`] model.
+    ///
+    /// # Arguments
+    ///
+    /// * `chain_a` - A chain name. Must match the name of a chain's folder in the root directory of the
+    ///   [chain registry](https://github.com/cosmos/chain-registry).
+    /// * `chain_b` - A chain name. Must match the name of a chain's folder in the root directory of the
+    ///   [chain registry](https://github.com/cosmos/chain-registry).
+    async fn get_path(&self, chain_a: &str, chain_b: &str) -> Result<Option<IBCPath>> {
+        let path = ibc_path_file(chain_a, chain_b);
+        let data = self.get_file_content(&path).await?;
+
+        parse_json(&path, &data).map(Some)
+    }
+
+    /// Retrieves the deserialized `assetlist.json` of each chain, fetching up to `concurrency` files at once. Results are
+    /// returned in the order of `names`, each with its own error if that file couldn't be retrieved or parsed.
+    async fn get_asset_lists(
+        &self,
+        names: &[String],
+        concurrency: usize,
+    ) -> Vec<(String, Result<AssetList>)> {
+        load_concurrently(names.to_vec(), concurrency, |name| {
+            read_json(self, assets_file(&name))
+        })
+        .await
+    }
+
+    /// Retrieves the deserialized `chain.json` of each chain, fetching up to `concurrency` files at once. Results are
+    /// returned in the order of `names`, each with its own error if that file couldn't be retrieved or parsed.
+    async fn get_chains(
+        &self,
+        names: &[String],
+        concurrency: usize,
+    ) -> Vec<(String, Result<ChainInfo>)> {
+        load_concurrently(names.to_vec(), concurrency, |name| {
+            read_json(self, chain_file(&name))
+        })
+        .await
+    }
+
+    /// Retrieves the deserialized IBC path json for each path name as returned by
+    /// [`list_paths`](RegistrySource::list_paths), fetching up to `concurrency` files at once. Results are returned in
+    /// the order of `path_names`, each with its own error if that file couldn't be retrieved or parsed.
+    async fn get_paths(
+        &self,
+        path_names: &[String],
+        concurrency: usize,
+    ) -> Vec<(String, Result<IBCPath>)> {
+        load_concurrently(path_names.to_vec(), concurrency, |name| {
+            read_json(self, format!("_IBC/{}.json", name))
+        })
+        .await
+    }
+
+    /// Lists the chains in the registry, then yields each chain's deserialized `assetlist.json` as soon as it has been
+    /// fetched and parsed, with up to `concurrency` requests in flight. Only fails if the listing does; errors for
+    /// individual files are yielded alongside the chain name.
+    async fn stream_asset_lists(
+        &self,
+        concurrency: usize,
+    ) -> Result<BoxStream<'_, (String, Result<AssetList>)>> {
+        let names = self.list_chains().await?;
+
+        Ok(load_unordered(names, concurrency, move |name| {
+            read_json(self, assets_file(&name))
+        }))
+    }
+
+    /// Lists the chains in the registry, then yields each chain's deserialized `chain.json` as soon as it has been
+    /// fetched and parsed, with up to `concurrency` requests in flight. Only fails if the listing does; errors for
+    /// individual files are yielded alongside the chain name.
+    async fn stream_chains(
+        &self,
+        concurrency: usize,
+    ) -> Result<BoxStream<'_, (String, Result<ChainInfo>)>> {
+        let names = self.list_chains().await?;
+
+        Ok(load_unordered(names, concurrency, move |name| {
+            read_json(self, chain_file(&name))
+        }))
+    }
+
+    /// Lists the IBC paths in the registry, then yields each deserialized path as soon as it has been fetched and
+    /// parsed, with up to `concurrency` requests in flight. Only fails if the listing does; errors for individual files
+    /// are yielded alongside the path name.
+    async fn stream_paths(
+        &self,
+        concurrency: usize,
+    ) -> Result<BoxStream<'_, (String, Result<IBCPath>)>> {
+        let names = self.list_paths().await?;
+
+        Ok(load_unordered(names, concurrency, move |name| {
+            read_json(self, format!("_IBC/{}.json", name))
+        }))
+    }
+
+    /// Like [`get_assets`](RegistrySource::get_assets), but reports every unknown field, type mismatch and missing
+    /// required field in `assetlist.json`. In [`ParseMode::Strict`] any of these is a [`RegistryError::Schema`].
+    async fn get_assets_checked(&self, name: &str, mode: ParseMode) -> Result<Parsed<AssetList>> {
+        let path = assets_file(name);
+        let data = self.get_file_content(&path).await?;
+
+        diagnostics::parse(&path, &data, mode)
+    }
+
+    /// Like [`get_chain`](RegistrySource::get_chain), but reports every unknown field, type mismatch and missing
+    /// required field in `chain.json`. In [`ParseMode::Strict`] any of these is a [`RegistryError::Schema`].
+    async fn get_chain_checked(&self, name: &str, mode: ParseMode) -> Result<Parsed<ChainInfo>> {
+        let path = chain_file(name);
+        let data = self.get_file_content(&path).await?;
+
+        diagnostics::parse(&path, &data, mode)
+    }
+
+    /// Like [`get_path`](RegistrySource::get_path), but reports every unknown field, type mismatch and missing
+    /// required field in the IBC path json. In [`ParseMode::Strict`] any of these is a [`RegistryError::Schema`].
+    async fn get_path_checked(
+        &self,
+        chain_a: &str,
+        chain_b: &str,
+        mode: ParseMode,
+    ) -> Result<Parsed<IBCPath>> {
+        let path = ibc_path_file(chain_a, chain_b);
+        let data = self.get_file_content(&path).await?;
+
+        diagnostics::parse(&path, &data, mode)
+    }
+}
+
+/// One network of a [`RegistrySource`], as returned by [`RegistrySource::network`]
+#[derive(Debug)]
+pub struct NetworkView<'a, S: ?Sized> {
+    source: &'a S,
+    network: Network,
+}
+
+impl<S: ?Sized> Clone for NetworkView<'_, S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S: ?Sized> Copy for NetworkView<'_, S> {}
+
+impl<S: ?Sized> NetworkView<'_, S> {
+    /// The network this view reads from
+    pub fn network(&self) -> Network {
+        self.network
+    }
+}
+
+#[async_trait]
+impl<S: RegistrySource + ?Sized> RegistrySource for NetworkView<'_, S> {
+    async fn list_dir(&self, dir: &str) -> Result<Vec<DirEntry>> {
+        self.source.list_dir(&self.network.join(dir)).await
+    }
+
+    async fn get_file_content(&self, path: &str) -> Result<String> {
+        self.source.get_file_content(&self.network.join(path)).await
+    }
+}
+
+// path names order the chain names alphabetically
+fn ibc_path_file(chain_a: &str, chain_b: &str) -> String {
+    format!(
+        "_IBC/{}-{}.json",
+        chain_a.min(chain_b),
+        chain_a.max(chain_b)
+    )
+}
+
+/// Whether a directory in the root of the registry holds a mainnet chain
+pub(crate) fn is_chain_dir(name: &str) -> bool {
+    !name.starts_with('_') && name != ".github" && name != Network::Testnet.dir()
+}
+
+fn non_cosmos_assets_file(name: &str) -> String {
+    format!("{}/{}/assetlist.json", NON_COSMOS_DIR, name)
+}
+
+/// Reads a chain's asset list from the root directory, falling back to `_non-cosmos`
+async fn any_asset_list<S>(source: &S, chain: &str) -> Result<AssetList>
+where
+    S: RegistrySource + ?Sized,
+{
+    match read_json(source, assets_file(chain)).await {
+        Err(err) if err.is_not_found() => read_json(source, non_cosmos_assets_file(chain)).await,
+        result => result,
+    }
+}
+
+pub(crate) fn assets_file(name: &str) -> String {
+    format!("{}/assetlist.json", name)
+}
+
+pub(crate) fn chain_file(name: &str) -> String {
+    format!("{}/chain.json", name)
+}
+
+/// Reads and deserializes a file from `source`
+pub(crate) async fn read_json<S, T>(source: &S, path: String) -> Result<T>
+where
+    S: RegistrySource + ?Sized,
+    T: DeserializeOwned,
+{
+    let data = source.get_file_content(&path).await?;
+
+    parse_json(&path, &data)
+}
+
+/// Pairs each name with the future that loads it
+fn load_each<T, F, Fut>(
+    names: Vec<String>,
+    load: F,
+) -> impl Stream<Item = impl Future<Output = (String, Result<T>)>>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    stream::iter(names).map(move |name| {
+        let fut = load(name.clone());
+        async move { (name, fut.await) }
+    })
+}
+
+/// Runs `load` for each name with at most `concurrency` in flight, keeping the results in the order of `names`
+async fn load_concurrently<T, F, Fut>(
+    names: Vec<String>,
+    concurrency: usize,
+    load: F,
+) -> Vec<(String, Result<T>)>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    load_each(names, load)
+        .buffered(concurrency.max(1))
+        .collect()
+        .await
+}
+
+/// Runs `load` for each name with at most `concurrency` in flight, yielding each result as soon as it's ready
+pub(crate) fn load_unordered<'a, T, F, Fut>(
+    names: Vec<String>,
+    concurrency: usize,
+    load: F,
+) -> BoxStream<'a, (String, Result<T>)>
+where
+    F: Fn(String) -> Fut + Send + 'a,
+    Fut: Future<Output = Result<T>> + Send + 'a,
+    T: Send + 'a,
+{
+    load_each(names, load)
+        .buffer_unordered(concurrency.max(1))
+        .boxed()
+}
+
+pub(crate) fn parse_json<T>(path: &str, data: &str) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    serde_json::from_str(data).map_err(|e| RegistryError::parse(path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assay::assay;
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Mutex,
+        },
+        time::Duration,
+    };
+    use tokio::sync::Notify;
+
+    /// Serves every chain after a delay that shrinks with its number, so later files finish first
+    #[derive(Default)]
+    struct SlowRegistry {
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl RegistrySource for SlowRegistry {
+        async fn list_dir(&self, _dir: &str) -> Result<Vec<DirEntry>> {
+            Ok((0..5)
+                .map(|i| DirEntry::dir(format!("chain{}", i)))
+                .collect())
+        }
+
+        async fn get_file_content(&self, path: &str) -> Result<String> {
+            let n = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(n, Ordering::SeqCst);
+
+            let name = path.split('/').next().unwrap();
+            let number: u64 = name.trim_start_matches("chain").parse().unwrap_or(0);
+            tokio::time::sleep(Duration::from_millis(50 - number * 4)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            if name == "broken" {
+                return Ok("{".to_string());
+            }
+            Ok(format!(r#"{{"chain_name": "{}"}}"#, name))
+        }
+    }
+
+    #[assay]
+    async fn bulk_getters_keep_order_and_limit_concurrency() {
+        let registry = SlowRegistry::default();
+        let mut names: Vec<String> = (0..10).map(|i| format!("chain{}", i)).collect();
+        names.insert(3, "broken".to_string());
+
+        let chains = registry.get_chains(&names, 4).await;
+
+        assert_eq!(
+            chains.iter().map(|(n, _)| n.clone()).collect::<Vec<_>>(),
+            names
+        );
+        for (name, chain) in &chains {
+            match chain {
+                Ok(chain) => assert_eq!(&chain.chain_name, name),
+                Err(err) => {
+                    assert_eq!(name, "broken");
+                    assert!(
+                        matches!(err, RegistryError::Parse { path, .. } if path == "broken/chain.json")
+                    );
+                }
+            }
+        }
+        assert_eq!(chains.iter().filter(|(_, c)| c.is_err()).count(), 1);
+        assert_eq!(registry.max_in_flight.load(Ordering::SeqCst), 4);
+    }
+
+    /// Serves chains in descending order of their number, each one only once the one after it has been served
+    struct ReversedRegistry {
+        turn: Mutex<u64>,
+        turn_changed: Notify,
+    }
+
+    #[async_trait]
+    impl RegistrySource for ReversedRegistry {
+        async fn list_dir(&self, _dir: &str) -> Result<Vec<DirEntry>> {
+            Ok((0..5)
+                .map(|i| DirEntry::dir(format!("chain{}", i)))
+                .collect())
+        }
+
+        async fn get_file_content(&self, path: &str) -> Result<String> {
+            let name = path.split('/').next().unwrap();
+            let number: u64 = name.trim_start_matches("chain").parse().unwrap();
+
+            loop {
+                // created before checking the turn so a change in between isn't missed
+                let changed = self.turn_changed.notified();
+                if *self.turn.lock().unwrap() == number {
+                    break;
+                }
+                changed.await;
+            }
+            *self.turn.lock().unwrap() = number.saturating_sub(1);
+            self.turn_changed.notify_waiters();
+
+            Ok(format!(r#"{{"chain_name": "{}"}}"#, name))
+        }
+    }
+
+    #[assay]
+    async fn streams_chains_as_they_arrive() {
+        let registry = ReversedRegistry {
+            turn: Mutex::new(4),
+            turn_changed: Notify::new(),
+        };
+        let names: Vec<String> = registry
+            .stream_chains(5)
+            .await
+            .unwrap()
+            .map(|(name, chain)| {
+                assert_eq!(chain.unwrap().chain_name, name);
+                name
+            })
+            .collect()
+            .await;
+
+        assert_eq!(
+            names,
+            vec!["chain4", "chain3", "chain2", "chain1", "chain0"]
+        );
+    }
+}
@@ -0,0 +1,130 @@
+//! A minimal HTTP server for exercising request handling without network access
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener},
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+/// A request received by a [`TestServer`]
+#[derive(Clone, Debug)]
+pub(crate) struct Request {
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// A response for a [`TestServer`] to send
+#[derive(Clone, Debug)]
+pub(crate) struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    // how long to wait between sending the headers and the body
+    pub body_delay: Option<Duration>,
+}
+
+impl Response {
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Response {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+            body_delay: None,
+        }
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn with_body_delay(mut self, delay: Duration) -> Self {
+        self.body_delay = Some(delay);
+        self
+    }
+}
+
+/// Serves each connection on a background thread with the provided handler until the process exits
+pub(crate) struct TestServer {
+    addr: SocketAddr,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+impl TestServer {
+    pub fn start<F>(handler: F) -> TestServer
+    where
+        F: Fn(&Request) -> Response + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let log = requests.clone();
+        let handler = Arc::new(handler);
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = match stream {
+                    Ok(s) => s,
+                    Err(_) => continue,
+                };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let path = line.split_whitespace().nth(1).unwrap_or("/").to_string();
+                let mut headers = Vec::new();
+
+                loop {
+                    line.clear();
+                    reader.read_line(&mut line).unwrap();
+                    let header = line.trim_end();
+                    if header.is_empty() {
+                        break;
+                    }
+                    if let Some((k, v)) = header.split_once(':') {
+                        headers.push((k.trim().to_string(), v.trim().to_string()));
+                    }
+                }
+
+                let request = Request { path, headers };
+                let response = handler(&request);
+                log.lock().unwrap().push(request);
+
+                let mut head = format!(
+                    "HTTP/1.1 {} Test\r\nContent-Length: {}\r\nConnection: close\r\n",
+                    response.status,
+                    response.body.len()
+                );
+                for (k, v) in &response.headers {
+                    head.push_str(&format!("{}: {}\r\n", k, v));
+                }
+                head.push_str("\r\n");
+                let _ = stream.write_all(head.as_bytes());
+                if let Some(delay) = response.body_delay {
+                    let _ = stream.flush();
+                    thread::sleep(delay);
+                }
+                let _ = stream.write_all(&response.body);
+            }
+        });
+
+        TestServer { addr, requests }
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+}
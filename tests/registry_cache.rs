@@ -1,7 +1,7 @@
-#![cfg(all(feature = "cache"))]
+#![cfg(feature = "cache")]
 #![cfg_attr(docsrs, doc(cfg(feature = "cache")))]
 use assay::assay;
-use chain_registry::{cache::RegistryCache, paths::Tag};
+use chain_registry::{cache::RegistryCache, local::LocalRegistry, paths::Tag};
 
 #[assay]
 async fn registry_cache_happy_path() {
@@ -50,3 +50,83 @@ async fn registry_cache_happy_path() {
         .iter()
         .for_each(|r| assert!(r.channels[0].tags.status.eq(&status)));
 }
+
+#[assay]
+async fn registry_cache_from_local() {
+    let registry = LocalRegistry::new(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/registry"
+    ));
+    let cache = RegistryCache::try_from_local(&registry)
+        .await
+        .expect("failed to initialize cache");
+
+    let result = cache
+        .get_path("osmosis", "cosmoshub")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(result.chain_1.chain_name, "cosmoshub");
+    assert_eq!(result.chain_2.chain_name, "osmosis");
+
+    let result = cache
+        .get_paths_filtered(Tag::Dex("osmosis".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(result.len(), 2);
+
+    let result = cache
+        .get_paths_filtered(Tag::Preferred(true))
+        .await
+        .unwrap();
+    assert_eq!(result.len(), 1);
+}
+
+#[assay]
+async fn registry_cache_with_concurrency_limit() {
+    let registry = LocalRegistry::new(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/registry"
+    ));
+    let cache = RegistryCache::try_from_source_with_concurrency(&registry, 1)
+        .await
+        .expect("failed to initialize cache");
+
+    let result = cache.get_path("juno", "osmosis").await.unwrap().unwrap();
+    assert_eq!(result.channels[0].chain_2.channel_id, "channel-42");
+}
+
+#[assay]
+async fn registry_cache_with_testnets() {
+    let registry = LocalRegistry::new(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/registry"
+    ));
+    let cache = RegistryCache::try_from_source_with_testnets(&registry, 4)
+        .await
+        .expect("failed to initialize cache");
+
+    let result = cache
+        .get_testnet_path("osmosistestnet", "junotestnet")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(result.chain_1.chain_name, "junotestnet");
+    assert!(cache
+        .get_path("junotestnet", "osmosistestnet")
+        .await
+        .unwrap()
+        .is_none());
+    assert!(cache.get_path("juno", "osmosis").await.unwrap().is_some());
+
+    let result = cache
+        .get_testnet_paths_filtered(Tag::Preferred(true))
+        .await
+        .unwrap();
+    assert_eq!(result.len(), 1);
+    let result = cache
+        .get_paths_filtered(Tag::Preferred(true))
+        .await
+        .unwrap();
+    assert_eq!(result.len(), 1);
+}
@@ -0,0 +1,260 @@
+use assay::assay;
+use chain_registry::{assets::AssetList, chain::ChainInfo, paths::IBCPath};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+use std::path::Path;
+
+fn round_trip<T: DeserializeOwned + Serialize>(original: &Value) -> (T, Value) {
+    let parsed: T = serde_json::from_value(original.clone()).unwrap();
+    let written = serde_json::from_str(&serde_json::to_string(&parsed).unwrap()).unwrap();
+
+    (parsed, written)
+}
+
+/// Converts every number to a float, since the models read `0` and `0.0` into the same value
+fn normalize_numbers(value: Value) -> Value {
+    match value {
+        Value::Number(n) => json!(n.as_f64().unwrap()),
+        Value::Array(items) => Value::Array(items.into_iter().map(normalize_numbers).collect()),
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(k, v)| (k, normalize_numbers(v)))
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+/// Round trips every registry file under `dir` with the model for its kind
+fn round_trip_dir(dir: &Path, checked: &mut usize) {
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            round_trip_dir(&path, checked);
+            continue;
+        }
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+
+        let original: Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        let in_ibc_dir = path.parent().unwrap().ends_with("_IBC");
+        let written = match path.file_name().unwrap().to_str().unwrap() {
+            "chain.json" => round_trip::<ChainInfo>(&original).1,
+            "assetlist.json" => round_trip::<AssetList>(&original).1,
+            _ if in_ibc_dir => round_trip::<IBCPath>(&original).1,
+            _ => continue,
+        };
+
+        assert_eq!(
+            normalize_numbers(written),
+            normalize_numbers(original),
+            "{} changed",
+            path.display()
+        );
+        *checked += 1;
+    }
+}
+
+#[assay]
+fn fixtures_round_trip_unchanged() {
+    let mut checked = 0;
+    round_trip_dir(
+        &Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/registry"),
+        &mut checked,
+    );
+
+    assert_eq!(checked, 11);
+}
+
+#[assay]
+fn chain_info_keeps_unknown_fields() {
+    let original = json!({
+        "$schema": "../chain.schema.json",
+        "chain_name": "osmosis",
+        "chain_type": "cosmos",
+        "status": "live",
+        "network_type": "mainnet",
+        "pretty_name": "Osmosis",
+        "description": "Osmosis is a decentralized exchange",
+        "chain_id": "osmosis-1",
+        "bech32_prefix": "osmo",
+        "daemon_name": "osmosisd",
+        "node_home": "$HOME/.osmosisd",
+        "slip44": 118,
+        "alternative_slip44s": [60],
+        "bech32_config": {"bech32PrefixAccAddr": "osmo", "bech32PrefixValAddr": "osmovaloper"},
+        "extra_codebase": [{"git_repo": "https://github.com/osmosis-labs/sidecar"}],
+        "genesis": {"genesis_url": "https://example.com/genesis.json", "genesis_sha256": "abc"},
+        "codebase": {
+            "git_repo": "https://github.com/osmosis-labs/osmosis",
+            "recommended_version": "v11.0.0",
+            "compatible_versions": ["v11.0.0"],
+            "binaries": {
+                "linux/amd64": "a",
+                "linux/arm64": "b",
+                "darwin/amd64": "c",
+                "darwin/arm64": "d",
+                "windows/amd64": "e",
+                "windows/arm64": "f"
+            },
+            "cosmos_sdk_version": "0.45",
+            "tendermint_version": "0.34",
+            "cosmwasm_version": "0.24",
+            "cosmwasm_enabled": true,
+            "ibc_go_version": "3.0.0",
+            "language": {"type": "go", "version": "1.21"},
+            "consensus": {"type": "cometbft", "version": "0.37.4", "repo": "https://github.com/osmosis-labs/cometbft"},
+            "ibc": {"type": "go", "version": "7.4.0", "ics_enabled": ["ics20-1"]}
+        },
+        "peers": {
+            "seeds": [{"id": "s", "address": "s:26656", "provider": "p"}],
+            "persistent_peers": [{"id": "p", "address": "p:26656", "provider": "p"}]
+        },
+        "apis": {
+            "rpc": [{"address": "https://rpc", "provider": "p", "archive": true}],
+            "rest": [{"address": "https://rest"}],
+            "grpc": [{"address": "grpc:9090"}],
+            "evm-http-jsonrpc": [{"address": "https://evm"}]
+        },
+        "fees": {"fee_tokens": [{
+            "denom": "uosmo",
+            "fixed_min_gas_price": 0.0,
+            "low_gas_price": 0.0025,
+            "average_gas_price": 0.025,
+            "high_gas_price": 0.04,
+            "gas_costs": {"cosmos_send": 100000}
+        }]},
+        "staking": {"staking_tokens": [{"denom": "uosmo"}], "lock_duration": {"epochs": 14}},
+        "website": "https://osmosis.zone/",
+        "update_link": "https://example.com/update",
+        "key_algos": ["secp256k1"],
+        "explorers": [{"kind": "mintscan", "url": "u", "tx_page": "t", "account_page": "a"}],
+        "keywords": ["dex"],
+        "logo_URIs": {"png": "osmo.png"},
+        "images": [{"png": "osmo.png", "theme": {"primary_color_hex": "#760dbb", "circle": true}}],
+        "mintscan_name": "osmosis"
+    });
+    let (parsed, written) = round_trip::<ChainInfo>(&original);
+
+    assert_eq!(written, original);
+    assert_eq!(parsed.extras["mintscan_name"], "osmosis");
+    assert_eq!(parsed.keywords, vec!["dex"]);
+    assert_eq!(parsed.chain_type.as_deref(), Some("cosmos"));
+    assert_eq!(parsed.alternative_slip44s, vec![60]);
+    assert_eq!(
+        parsed.extra_codebase[0].git_repo,
+        "https://github.com/osmosis-labs/sidecar"
+    );
+    assert_eq!(
+        parsed
+            .bech32_config
+            .unwrap()
+            .bech32_prefix_val_addr
+            .as_deref(),
+        Some("osmovaloper")
+    );
+    assert_eq!(
+        parsed.codebase.consensus.unwrap().type_field.as_deref(),
+        Some("cometbft")
+    );
+    assert_eq!(
+        parsed.peers.persistent_peers[0].provider.as_deref(),
+        Some("p")
+    );
+    assert_eq!(parsed.images[0].theme.as_ref().unwrap().circle, Some(true));
+    assert_eq!(parsed.genesis.genesis_sha256.as_deref(), Some("abc"));
+    assert_eq!(parsed.apis.rpc[0].archive, Some(true));
+    assert!(parsed.apis.evm_http_jsonrpc[0].extras.is_empty());
+}
+
+#[assay]
+fn asset_list_keeps_unknown_fields() {
+    let original = json!({
+        "$schema": "../assetlist.schema.json",
+        "chain_name": "osmosis",
+        "assets": [{
+            "description": "The native token of Osmosis",
+            "denom_units": [
+                {"denom": "uosmo", "exponent": 0},
+                {"denom": "osmo", "exponent": 6, "aliases": ["osmosis"]}
+            ],
+            "base": "uosmo",
+            "name": "Osmosis",
+            "display": "osmo",
+            "symbol": "OSMO",
+            "logo_URIs": {"png": "osmo.png", "svg": "osmo.svg"},
+            "coingecko_id": "osmosis",
+            "type_asset": "sdk.coin",
+            "images": [{"png": "osmo.png"}]
+        }]
+    });
+    let (parsed, written) = round_trip::<AssetList>(&original);
+
+    assert_eq!(written, original);
+    assert_eq!(parsed.schema, "../assetlist.schema.json");
+    assert_eq!(
+        parsed.assets[0].denom_units[1].extras["aliases"],
+        json!(["osmosis"])
+    );
+}
+
+#[assay]
+fn ibc_path_keeps_unknown_fields() {
+    let original = json!({
+        "$schema": "../ibc_data.schema.json",
+        "chain_1": {"chain_name": "juno", "client_id": "07-tendermint-0", "connection_id": "connection-0"},
+        "chain_2": {"chain_name": "osmosis", "client_id": "07-tendermint-1457", "connection_id": "connection-1142"},
+        "channels": [{
+            "chain_1": {"channel_id": "channel-0", "port_id": "transfer"},
+            "chain_2": {"channel_id": "channel-42", "port_id": "transfer"},
+            "ordering": "unordered",
+            "version": "ics20-1",
+            "description": "fungible tokens",
+            "tags": {"dex": "osmosis", "preferred": true, "properties": "", "status": "live"}
+        }],
+        "operators": [{"chain_1": {"address": "juno1"}}]
+    });
+    let (parsed, written) = round_trip::<IBCPath>(&original);
+
+    assert_eq!(written, original);
+    assert_eq!(parsed.channels[0].extras["description"], "fungible tokens");
+}
+
+#[assay]
+fn empty_and_absent_fields_round_trip_unchanged() {
+    let chain = json!({
+        "chain_name": "osmosis",
+        "chain_id": "osmosis-1",
+        "codebase": {},
+        "fees": {},
+        "staking": {"lock_duration": {"epochs": 14}},
+        "apis": {"grpc": []},
+        "explorers": []
+    });
+    let (mut parsed, written) = round_trip::<ChainInfo>(&chain);
+
+    assert_eq!(written, chain);
+
+    // an absent field is written once it's set
+    parsed.staking.staking_tokens.push(Default::default());
+    let written = serde_json::to_value(&parsed).unwrap();
+    assert_eq!(written["staking"]["staking_tokens"], json!([{}]));
+
+    let path = json!({
+        "chain_1": {"chain_name": "juno"},
+        "chain_2": {"chain_name": "osmosis", "client_id": ""},
+        "channels": [{
+            "chain_1": {"channel_id": "channel-0", "port_id": "transfer"},
+            "chain_2": {"channel_id": "channel-42", "port_id": "transfer"},
+            "tags": {}
+        }, {
+            "chain_1": {"channel_id": "channel-1", "port_id": "wasm"},
+            "chain_2": {"channel_id": "channel-43", "port_id": "transfer"},
+            "tags": {"preferred": false, "properties": ""}
+        }]
+    });
+    let (_, written) = round_trip::<IBCPath>(&path);
+
+    assert_eq!(written, path);
+}